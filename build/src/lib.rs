@@ -0,0 +1,22 @@
+//! Generates custom geometric algebras from the `build.rs` of a downstream crate
+//!
+//! The downstream crate needs `geometric_algebra` as a regular dependency and this crate as a build dependency.
+//! In `build.rs` the algebra is described either by a compact descriptor string or by a TOML descriptor file:
+//!
+//! ```no_run
+//! // build.rs
+//! let out_dir = std::env::var("OUT_DIR").unwrap();
+//! geometric_algebra_build::generate("vga2d:1,1;Scalar:1;Rotor:1,e01;Vector:e0,e1", &out_dir).unwrap();
+//! geometric_algebra_build::generate_from_config("algebras/vga3d.toml", &out_dir).unwrap();
+//! println!("cargo:rerun-if-changed=algebras/vga3d.toml");
+//! ```
+//!
+//! The generated files are then included into modules of the downstream crate:
+//!
+//! ```ignore
+//! #[allow(clippy::assign_op_pattern, clippy::from_over_into)]
+//! mod vga2d {
+//!     include!(concat!(env!("OUT_DIR"), "/vga2d.rs"));
+//! }
+//! ```
+pub use geometric_algebra_codegen::{generate, generate_from_config, Error, ParseError};
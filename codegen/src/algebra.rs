@@ -22,6 +22,11 @@ impl<'a> GeometricAlgebra<'a> {
         })
     }
 
+    /// Checks if the element contains a generator which squares to zero (the bulk, e.g. of ideal points in PGA)
+    pub fn is_degenerate(&self, element: &BasisElement) -> bool {
+        element.component_bits().any(|bit| self.generator_squares[bit] == 0)
+    }
+
     /// Sorted list of all basis blades, in canonical order.
     pub fn sorted_basis(&self) -> Vec<BasisElement> {
         let mut basis_elements = self.basis().collect::<Vec<BasisElement>>();
@@ -47,23 +52,36 @@ impl BasisElement {
     }
 
     /// Parses a basis blade from a string like "-e13"
-    pub fn parse(mut name: &str, algebra: &GeometricAlgebra) -> Self {
+    pub fn parse(mut name: &str, algebra: &GeometricAlgebra) -> Result<Self, String> {
         let mut result = Self::from_index(0);
-        if name.starts_with('-') {
-            name = &name[1..];
+        if let Some(stripped) = name.strip_prefix('-') {
+            name = stripped;
             result.scalar = -1;
         }
         if name == "1" {
-            return result;
+            return Ok(result);
         }
-        let mut generator_indices = name.chars();
-        assert_eq!(generator_indices.next().unwrap(), 'e');
-        for generator_index in generator_indices {
-            let generator_index = generator_index.to_digit(16).unwrap();
-            assert!((generator_index as usize) < algebra.generator_squares.len());
+        let generator_indices = match name.strip_prefix('e') {
+            Some(generator_indices) if !generator_indices.is_empty() => generator_indices,
+            _ => return Err("expected `1` or `e` followed by generator indices".to_string()),
+        };
+        for generator_index in generator_indices.chars() {
+            let generator_index = generator_index
+                .to_digit(16)
+                .ok_or_else(|| format!("`{}` is not a hexadecimal generator index", generator_index))? as usize;
+            if generator_index >= algebra.generator_squares.len() {
+                return Err(format!(
+                    "generator index {:X} is out of range for an algebra with {} generators",
+                    generator_index,
+                    algebra.generator_squares.len()
+                ));
+            }
+            if result.index & (1 << generator_index) != 0 {
+                return Err(format!("generator index {:X} occurs more than once", generator_index));
+            }
             result = BasisElement::product(&result, &Self::from_index(1 << generator_index), algebra);
         }
-        result
+        Ok(result)
     }
 
     /// Number of basis vectors in the element
@@ -187,6 +205,24 @@ impl Involution {
         }
     }
 
+    /// Sets the elements which do not satisfy the predicate to zero.
+    pub fn masked<F>(&self, mask: F) -> Self
+    where
+        F: Fn(&BasisElement) -> bool,
+    {
+        Self {
+            terms: self
+                .terms
+                .iter()
+                .map(|(key, value)| {
+                    let mut element = value.clone();
+                    element.scalar *= if mask(value) { 1 } else { 0 };
+                    (key.clone(), element)
+                })
+                .collect(),
+        }
+    }
+
     /// Applies dual to all target values in the involution.
     pub fn dual(&self, algebra: &GeometricAlgebra) -> Self {
         Self {
@@ -197,13 +233,18 @@ impl Involution {
     /// Predefined involutions for geometric algebra.
     pub fn involutions(algebra: &GeometricAlgebra) -> Vec<(&'static str, Self)> {
         let involution = Self::identity(algebra);
-        vec![
+        let mut involutions = vec![
             ("Neg", involution.negated(|_grade| true)),
             ("Automorphism", involution.negated(|grade| grade % 2 == 1)),
             ("Reversal", involution.negated(|grade| grade % 4 >= 2)),
             ("Conjugation", involution.negated(|grade| (grade + 3) % 4 < 2)),
             ("Dual", involution.dual(algebra)),
-        ]
+        ];
+        if algebra.generator_squares.contains(&0) {
+            involutions.push(("Bulk", involution.masked(|element| algebra.is_degenerate(element))));
+            involutions.push(("Weight", involution.masked(|element| !algebra.is_degenerate(element))));
+        }
+        involutions
     }
 }
 
@@ -295,11 +336,13 @@ pub struct MultiVectorClassRegistry {
 impl MultiVectorClassRegistry {
     /// Add class to registry
     pub fn register(&mut self, class: MultiVectorClass) {
-        self.index_by_signature.insert(class.signature(), self.classes.len());
+        self.index_by_signature.entry(class.signature()).or_insert(self.classes.len());
         self.classes.push(class);
     }
 
     /// Get class by signature
+    ///
+    /// If multiple classes have the same basis elements, this is the one registered first.
     pub fn get(&self, signature: &[BasisElementIndex]) -> Option<&MultiVectorClass> {
         self.index_by_signature.get(signature).map(|index| &self.classes[*index])
     }
@@ -309,5 +352,6 @@ impl MultiVectorClassRegistry {
 #[derive(PartialEq, Eq, Debug)]
 pub struct MultiVectorClass {
     pub class_name: String,
+    pub doc: Option<String>,
     pub grouped_basis: Vec<Vec<BasisElement>>,
 }
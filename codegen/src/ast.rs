@@ -1,6 +1,32 @@
 /// AST node for data types
 use crate::algebra::MultiVectorClass;
 
+/// Floating point type of the scalars which all multi vector elements are made of
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ScalarType {
+    F32, // Single precision, backed by `Simd32xN`
+    F64, // Double precision, backed by `Simd64xN`
+}
+
+impl ScalarType {
+    /// Parses the name of the scalar type as used in Rust ("f32" or "f64")
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "f32" => Some(Self::F32),
+            "f64" => Some(Self::F64),
+            _ => None,
+        }
+    }
+
+    /// Name of the scalar type as used in Rust
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::F32 => "f32",
+            Self::F64 => "f64",
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub enum DataType<'a> {
     Integer,                           // Basic integer type
@@ -50,6 +76,7 @@ pub enum ExpressionContent<'a> {
     Subtract(Box<Expression<'a>>, Box<Expression<'a>>),
     Multiply(Box<Expression<'a>>, Box<Expression<'a>>),
     Divide(Box<Expression<'a>>, Box<Expression<'a>>),
+    MultiplyAdd(Box<Expression<'a>>, Box<Expression<'a>>, Box<Expression<'a>>), // a * b + c, see [fuse_multiply_add](crate::compile::fuse_multiply_add)
 
     // Comparison and logical operations
     LessThan(Box<Expression<'a>>, Box<Expression<'a>>),
@@ -101,7 +128,9 @@ impl<'a> Parameter<'a> {
 #[derive(PartialEq, Eq, Clone)]
 pub enum AstNode<'a> {
     None,
-    Preamble,
+    Preamble {
+        crate_path: &'a str, // Path of the `geometric_algebra` crate, which provides the SIMD types and traits
+    },
     ClassDefinition {
         class: &'a MultiVectorClass,
     },
@@ -0,0 +1,478 @@
+/// C code generation from AST
+///
+/// Emits a self-contained header which is valid C99 and C++.
+/// As C has no vector types, every SIMD vector expression is expanded into one scalar expression per component.
+use crate::{
+    algebra::MultiVectorClass,
+    ast::{AstNode, DataType, Expression, ExpressionContent, ScalarType},
+    emit::{camel_to_snake_case, emit_element_name, emit_indentation},
+};
+
+/// Emits the C representation of a scalar type
+fn emit_scalar_type<W: std::io::Write>(collector: &mut W, scalar_type: ScalarType) -> std::io::Result<()> {
+    match scalar_type {
+        ScalarType::F32 => collector.write_all(b"float"),
+        ScalarType::F64 => collector.write_all(b"double"),
+    }
+}
+
+/// Emits the C representation of a data type (SIMD vectors only exist as members of classes)
+fn emit_data_type<W: std::io::Write>(collector: &mut W, data_type: &DataType, scalar_type: ScalarType) -> std::io::Result<()> {
+    match data_type {
+        DataType::Integer => collector.write_all(b"int"),
+        DataType::SimdVector(_size) => emit_scalar_type(collector, scalar_type),
+        DataType::MultiVector(class) if class.is_scalar() => emit_scalar_type(collector, scalar_type), // Scalar multivectors
+        DataType::MultiVector(class) => collector.write_all(class.class_name.as_bytes()),              // Other multivectors
+    }
+}
+
+/// Emits a floating point literal of the scalar type
+fn emit_literal<W: std::io::Write>(collector: &mut W, value: isize, scalar_type: ScalarType) -> std::io::Result<()> {
+    match scalar_type {
+        ScalarType::F32 => collector.write_fmt(format_args!("{:.1}f", value as f32)),
+        ScalarType::F64 => collector.write_fmt(format_args!("{:.1}", value as f64)),
+    }
+}
+
+/// Emits the access of a component of a group, where groups of size 1 are plain scalars
+fn emit_group_access<W: std::io::Write>(
+    collector: &mut W,
+    inner_expression: &Expression,
+    group_size: usize,
+    array_index: usize,
+    component_index: usize,
+    scalar_type: ScalarType,
+) -> std::io::Result<()> {
+    emit_expression(collector, inner_expression, 0, scalar_type)?;
+    if !inner_expression.is_scalar() {
+        collector.write_fmt(format_args!(".groups.g{}", array_index))?;
+        if group_size > 1 {
+            collector.write_fmt(format_args!("[{}]", component_index))?;
+        }
+    }
+    Ok(())
+}
+
+/// Recursively emits C code for the given component of an expression
+fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression, component: usize, scalar_type: ScalarType) -> std::io::Result<()> {
+    match &expression.content {
+        ExpressionContent::None => unreachable!(),
+
+        // Variable reference
+        ExpressionContent::Variable(_data_type, name) => {
+            collector.write_all(name.as_bytes())?;
+        }
+
+        // Special case for scalar constructor calls
+        ExpressionContent::InvokeClassMethod(class, "Constructor", arguments) if class.is_scalar() => {
+            emit_expression(collector, &arguments[0].1, 0, scalar_type)?;
+        }
+
+        // Constructor call, which takes every element as a separate argument
+        ExpressionContent::InvokeClassMethod(class, "Constructor", arguments) => {
+            camel_to_snake_case(collector, &class.class_name)?;
+            collector.write_all(b"_new(")?;
+            for (j, (group, (_argument_class, argument))) in class.grouped_basis.iter().zip(arguments.iter()).enumerate() {
+                for i in 0..group.len() {
+                    if j > 0 || i > 0 {
+                        collector.write_all(b", ")?;
+                    }
+                    emit_expression(collector, argument, i, scalar_type)?;
+                }
+            }
+            collector.write_all(b")")?;
+        }
+
+        // Method invocations (both class and instance methods)
+        ExpressionContent::InvokeClassMethod(_, _, arguments) | ExpressionContent::InvokeInstanceMethod(_, _, _, _, arguments) => {
+            match &expression.content {
+                // Instance method call
+                ExpressionContent::InvokeInstanceMethod(result_class, inner_expression, method_name, _, _) => {
+                    // Build function name from result class, argument classes, and method name
+                    if let DataType::MultiVector(result_class) = result_class {
+                        camel_to_snake_case(collector, &result_class.class_name)?;
+                        collector.write_all(b"_")?;
+                    }
+                    for (argument_class, _argument) in arguments.iter() {
+                        if let DataType::MultiVector(argument_class) = argument_class {
+                            camel_to_snake_case(collector, &argument_class.class_name)?;
+                            collector.write_all(b"_")?;
+                        }
+                    }
+                    camel_to_snake_case(collector, method_name)?;
+                    collector.write_all(b"(")?;
+                    emit_expression(collector, inner_expression, 0, scalar_type)?;
+                    if !arguments.is_empty() {
+                        collector.write_all(b", ")?;
+                    }
+                }
+
+                // Static method call
+                ExpressionContent::InvokeClassMethod(class, method_name, _) => {
+                    camel_to_snake_case(collector, &class.class_name)?;
+                    collector.write_all(b"_")?;
+                    camel_to_snake_case(collector, method_name)?;
+                    collector.write_all(b"(")?;
+                }
+                _ => unreachable!(),
+            }
+            for (i, (_argument_class, argument)) in arguments.iter().enumerate() {
+                if i > 0 {
+                    collector.write_all(b", ")?;
+                }
+                emit_expression(collector, argument, 0, scalar_type)?;
+            }
+            collector.write_all(b")")?;
+        }
+
+        // Type conversion
+        ExpressionContent::Conversion(source_class, destination_class, inner_expression) => {
+            camel_to_snake_case(collector, &source_class.class_name)?;
+            collector.write_all(b"_")?;
+            camel_to_snake_case(collector, &destination_class.class_name)?;
+            collector.write_all(b"_into(")?;
+            emit_expression(collector, inner_expression, 0, scalar_type)?;
+            collector.write_all(b")")?;
+        }
+
+        // Ternary conditional operator, which also works on structs in C
+        ExpressionContent::Select(condition_expression, then_expression, else_expression) => {
+            collector.write_all(b"(")?;
+            emit_expression(collector, condition_expression, 0, scalar_type)?;
+            collector.write_all(b") ? ")?;
+            emit_expression(collector, then_expression, component, scalar_type)?;
+            collector.write_all(b" : ")?;
+            emit_expression(collector, else_expression, component, scalar_type)?;
+        }
+
+        // Group of a multivector
+        ExpressionContent::Access(inner_expression, array_index) => {
+            emit_group_access(collector, inner_expression, expression.size, *array_index, component, scalar_type)?;
+        }
+
+        // Component reordering
+        ExpressionContent::Swizzle(inner_expression, indices) => {
+            emit_expression(collector, inner_expression, indices[component], scalar_type)?;
+        }
+
+        // Component of a potentially different group, a single index pair is splatted
+        ExpressionContent::Gather(inner_expression, indices) => {
+            let (array_index, component_index) = indices[if indices.len() == 1 { 0 } else { component }];
+            emit_group_access(
+                collector,
+                inner_expression,
+                inner_expression.size,
+                array_index,
+                component_index,
+                scalar_type,
+            )?;
+        }
+
+        // Constant value, a single value is splatted
+        ExpressionContent::Constant(data_type, values) => {
+            let value = values[if values.len() == 1 { 0 } else { component }];
+            match data_type {
+                DataType::Integer => collector.write_fmt(format_args!("{}", value))?,
+                DataType::SimdVector(_size) => emit_literal(collector, value, scalar_type)?,
+                _ => unreachable!(),
+            }
+        }
+
+        // Mathematical function
+        ExpressionContent::SquareRoot(inner_expression) => {
+            collector.write_all(match scalar_type {
+                ScalarType::F32 => b"sqrtf(",
+                ScalarType::F64 => b"sqrt(",
+            })?;
+            emit_expression(collector, inner_expression, component, scalar_type)?;
+            collector.write_all(b")")?;
+        }
+
+        // Binary operations
+        // Fused multiply-add, which is left to the compiler as `fma()` is slow without hardware support
+        ExpressionContent::MultiplyAdd(a, b, c) => {
+            emit_expression(collector, c, component, scalar_type)?;
+            collector.write_all(b" + ")?;
+            emit_expression(collector, a, component, scalar_type)?;
+            collector.write_all(b" * ")?;
+            emit_expression(collector, b, component, scalar_type)?;
+        }
+
+        ExpressionContent::Add(lhs, rhs)
+        | ExpressionContent::Subtract(lhs, rhs)
+        | ExpressionContent::Multiply(lhs, rhs)
+        | ExpressionContent::Divide(lhs, rhs)
+        | ExpressionContent::LessThan(lhs, rhs)
+        | ExpressionContent::Equal(lhs, rhs)
+        | ExpressionContent::LogicAnd(lhs, rhs)
+        | ExpressionContent::BitShiftRight(lhs, rhs) => {
+            // Add parentheses for logical AND to ensure correct precedence
+            if let ExpressionContent::LogicAnd(_, _) = expression.content {
+                collector.write_all(b"(")?;
+            }
+            emit_expression(collector, lhs, component, scalar_type)?;
+            collector.write_all(match expression.content {
+                ExpressionContent::Add(_, _) => b" + ",
+                ExpressionContent::Subtract(_, _) => b" - ",
+                ExpressionContent::Multiply(_, _) => b" * ",
+                ExpressionContent::Divide(_, _) => b" / ",
+                ExpressionContent::LessThan(_, _) => b" < ",
+                ExpressionContent::Equal(_, _) => b" == ",
+                ExpressionContent::LogicAnd(_, _) => b" & ",
+                ExpressionContent::BitShiftRight(_, _) => b" >> ",
+                _ => unreachable!(),
+            })?;
+            emit_expression(collector, rhs, component, scalar_type)?;
+            if let ExpressionContent::LogicAnd(_, _) = expression.content {
+                collector.write_all(b")")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Main function to emit C code for an AST node
+pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, scalar_type: ScalarType, indentation: usize) -> std::io::Result<()> {
+    emit_code_with_qualifiers(collector, ast_node, scalar_type, indentation, "static inline")
+}
+
+/// Emits C code for an AST node, declaring all functions with the given qualifiers (used by C dialects like CUDA)
+pub fn emit_code_with_qualifiers<W: std::io::Write>(
+    collector: &mut W,
+    ast_node: &AstNode,
+    scalar_type: ScalarType,
+    indentation: usize,
+    function_qualifiers: &str,
+) -> std::io::Result<()> {
+    match ast_node {
+        AstNode::None => {}
+
+        // Include guard, dependencies and the alignment of SIMD vectors
+        AstNode::Preamble { .. } => {
+            collector.write_all(b"#pragma once\n#include <math.h>\n#include <stdlib.h>\n\n")?;
+            collector.write_all(b"#ifndef GEOMETRIC_ALGEBRA_ALIGN\n")?;
+            collector.write_all(b"#if defined(__cplusplus)\n#define GEOMETRIC_ALGEBRA_ALIGN(alignment) alignas(alignment)\n")?;
+            collector.write_all(b"#elif defined(_MSC_VER)\n#define GEOMETRIC_ALGEBRA_ALIGN(alignment) __declspec(align(alignment))\n")?;
+            collector.write_all(b"#else\n#define GEOMETRIC_ALGEBRA_ALIGN(alignment) __attribute__((aligned(alignment)))\n#endif\n#endif\n\n")?;
+
+            // Has to match the alignment of the Rust SIMD vectors, which depends on the enabled target features
+            collector.write_all(match scalar_type {
+                ScalarType::F32 => b"#ifndef GEOMETRIC_ALGEBRA_F32_ALIGNMENT\n#define GEOMETRIC_ALGEBRA_F32_ALIGNMENT 16\n#endif\n\n",
+                ScalarType::F64 => b"#ifndef GEOMETRIC_ALGEBRA_F64_ALIGNMENT\n#define GEOMETRIC_ALGEBRA_F64_ALIGNMENT 32\n#endif\n\n",
+            })?;
+        }
+
+        // Union of the groups and the elements, with the same layout as the Rust union
+        AstNode::ClassDefinition { class } => {
+            if class.is_scalar() {
+                return Ok(()); // Skip scalar classes
+            }
+            // Generate union with a struct of groups
+            if let Some(doc) = &class.doc {
+                for line in doc.lines() {
+                    collector.write_fmt(format_args!("//{}{}\n", if line.is_empty() { "" } else { " " }, line))?;
+                }
+            }
+            collector.write_fmt(format_args!("typedef union {} {{\n", class.class_name))?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_all(b"struct {\n")?;
+            let mut element_count = 0;
+            for (i, group) in class.grouped_basis.iter().enumerate() {
+                // Comment showing the basis elements in this group
+                emit_indentation(collector, indentation + 2)?;
+                collector.write_all(b"// ")?;
+                for (i, element) in group.iter().enumerate() {
+                    if i > 0 {
+                        collector.write_all(b", ")?;
+                    }
+                    collector.write_fmt(format_args!("{}", element))?;
+                }
+                collector.write_all(b"\n")?;
+
+                // Groups of more than one element occupy a whole SIMD vector
+                emit_indentation(collector, indentation + 2)?;
+                if group.len() == 1 {
+                    emit_scalar_type(collector, scalar_type)?;
+                    collector.write_fmt(format_args!(" g{};\n", i))?;
+                    element_count += 1;
+                } else if group.len() == 8 {
+                    // Groups of eight elements are aligned to their size, like `Simd32x8` and `Simd64x8` in Rust
+                    collector.write_fmt(format_args!(
+                        "GEOMETRIC_ALGEBRA_ALIGN({}) ",
+                        match scalar_type {
+                            ScalarType::F32 => 32,
+                            ScalarType::F64 => 64,
+                        }
+                    ))?;
+                    emit_scalar_type(collector, scalar_type)?;
+                    collector.write_fmt(format_args!(" g{}[8];\n", i))?;
+                    element_count += 8;
+                } else {
+                    collector.write_fmt(format_args!(
+                        "GEOMETRIC_ALGEBRA_ALIGN(GEOMETRIC_ALGEBRA_{}_ALIGNMENT) ",
+                        scalar_type.name().to_uppercase()
+                    ))?;
+                    emit_scalar_type(collector, scalar_type)?;
+                    collector.write_fmt(format_args!(" g{}[4];\n", i))?;
+                    element_count += 4;
+                }
+            }
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_all(b"} groups;\n")?;
+
+            // Raw elements array
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_all(b"// ")?;
+            for (j, group) in class.grouped_basis.iter().enumerate() {
+                for (i, element) in group.iter().enumerate() {
+                    if j > 0 || i > 0 {
+                        collector.write_all(b", ")?;
+                    }
+                    collector.write_fmt(format_args!("{}", element))?;
+                }
+                if group.len() > 1 {
+                    for _ in group.len()..MultiVectorClass::simd_width(group.len()) {
+                        collector.write_all(b", 0")?;
+                    }
+                }
+            }
+            collector.write_all(b"\n")?;
+            emit_indentation(collector, indentation + 1)?;
+            emit_scalar_type(collector, scalar_type)?;
+            collector.write_fmt(format_args!(" elements[{}];\n", element_count))?;
+            emit_indentation(collector, indentation)?;
+            collector.write_fmt(format_args!("}} {};\n\n", class.class_name))?;
+
+            // Constructor function taking the elements, like `new()` in Rust
+            emit_indentation(collector, indentation)?;
+            collector.write_fmt(format_args!("{} {} ", function_qualifiers, class.class_name))?;
+            camel_to_snake_case(collector, &class.class_name)?;
+            collector.write_all(b"_new(")?;
+            for (j, group) in class.grouped_basis.iter().enumerate() {
+                for (i, element) in group.iter().enumerate() {
+                    if j > 0 || i > 0 {
+                        collector.write_all(b", ")?;
+                    }
+                    emit_scalar_type(collector, scalar_type)?;
+                    collector.write_all(b" ")?;
+                    emit_element_name(collector, element)?;
+                }
+            }
+            collector.write_all(b") {\n")?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_fmt(format_args!("{} result;\n", class.class_name))?;
+            for (j, group) in class.grouped_basis.iter().enumerate() {
+                for (i, element) in group.iter().enumerate() {
+                    emit_indentation(collector, indentation + 1)?;
+                    collector.write_fmt(format_args!("result.groups.g{}", j))?;
+                    if group.len() > 1 {
+                        collector.write_fmt(format_args!("[{}]", i))?;
+                    }
+                    collector.write_all(b" = ")?;
+                    emit_element_name(collector, element)?;
+                    collector.write_all(b";\n")?;
+                }
+                if group.len() > 1 {
+                    for i in group.len()..MultiVectorClass::simd_width(group.len()) {
+                        emit_indentation(collector, indentation + 1)?;
+                        collector.write_fmt(format_args!("result.groups.g{}[{}] = ", j, i))?;
+                        emit_literal(collector, 0, scalar_type)?;
+                        collector.write_all(b";\n")?;
+                    }
+                }
+            }
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_all(b"return result;\n")?;
+            emit_indentation(collector, indentation)?;
+            collector.write_all(b"}\n\n")?;
+        }
+
+        // Return statement
+        AstNode::ReturnStatement { expression } => {
+            collector.write_all(b"return ")?;
+            emit_expression(collector, expression, 0, scalar_type)?;
+            collector.write_all(b";\n")?;
+        }
+
+        // Variable declaration/assignment
+        AstNode::VariableAssignment { name, data_type, expression } => {
+            if let Some(data_type) = data_type {
+                emit_data_type(collector, data_type, scalar_type)?;
+                collector.write_all(b" ")?;
+            }
+            collector.write_fmt(format_args!("{} = ", name))?;
+            emit_expression(collector, expression, 0, scalar_type)?;
+            collector.write_all(b";\n")?;
+        }
+
+        // If and while blocks
+        AstNode::IfThenBlock { condition, body } | AstNode::WhileLoopBlock { condition, body } => {
+            collector.write_all(match &ast_node {
+                AstNode::IfThenBlock { .. } => b"if",
+                AstNode::WhileLoopBlock { .. } => b"while",
+                _ => unreachable!(),
+            })?;
+            collector.write_all(b"(")?;
+            emit_expression(collector, condition, 0, scalar_type)?;
+            collector.write_all(b") {\n")?;
+            for statement in body.iter() {
+                emit_indentation(collector, indentation + 1)?;
+                emit_code_with_qualifiers(collector, statement, scalar_type, indentation + 1, function_qualifiers)?;
+            }
+            emit_indentation(collector, indentation)?;
+            collector.write_all(b"}\n")?;
+        }
+
+        // Function definition
+        AstNode::TraitImplementation { result, parameters, body } => {
+            collector.write_fmt(format_args!("{} ", function_qualifiers))?;
+            emit_data_type(collector, &result.data_type, scalar_type)?;
+            collector.write_all(b" ")?;
+
+            // Generate function name based on parameter count and types
+            match parameters.len() {
+                0 => camel_to_snake_case(collector, &result.multi_vector_class().class_name)?,
+                1 if result.name == "Into" => {
+                    camel_to_snake_case(collector, &parameters[0].multi_vector_class().class_name)?;
+                    collector.write_all(b"_")?;
+                    camel_to_snake_case(collector, &result.multi_vector_class().class_name)?;
+                }
+                1 => camel_to_snake_case(collector, &parameters[0].multi_vector_class().class_name)?,
+                2 if !matches!(parameters[1].data_type, DataType::MultiVector(_)) => {
+                    camel_to_snake_case(collector, &parameters[0].multi_vector_class().class_name)?
+                }
+                2 => {
+                    camel_to_snake_case(collector, &parameters[0].multi_vector_class().class_name)?;
+                    collector.write_all(b"_")?;
+                    camel_to_snake_case(collector, &parameters[1].multi_vector_class().class_name)?;
+                }
+                _ => unreachable!(),
+            }
+            collector.write_all(b"_")?;
+            camel_to_snake_case(collector, result.name)?;
+
+            // Function parameters
+            collector.write_all(b"(")?;
+            for (i, parameter) in parameters.iter().enumerate() {
+                if i > 0 {
+                    collector.write_all(b", ")?;
+                }
+                emit_data_type(collector, &parameter.data_type, scalar_type)?;
+                collector.write_fmt(format_args!(" {}", parameter.name))?;
+            }
+            if parameters.is_empty() {
+                collector.write_all(b"void")?;
+            }
+            collector.write_all(b") {\n")?;
+
+            // Function body
+            for statement in body.iter() {
+                emit_indentation(collector, indentation + 1)?;
+                emit_code_with_qualifiers(collector, statement, scalar_type, indentation + 1, function_qualifiers)?;
+            }
+            emit_indentation(collector, indentation)?;
+            collector.write_all(b"}\n\n")?;
+        }
+    }
+    Ok(())
+}
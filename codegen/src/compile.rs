@@ -1,5 +1,5 @@
 use crate::{
-    algebra::{BasisElement, BasisElementIndex, Involution, MultiVectorClass, MultiVectorClassRegistry, Product},
+    algebra::{BasisElement, BasisElementIndex, GeometricAlgebra, Involution, MultiVectorClass, MultiVectorClassRegistry, Product},
     ast::{AstNode, DataType, Expression, ExpressionContent, Parameter},
 };
 
@@ -141,6 +141,312 @@ pub fn simplify_and_legalize(expression: Box<Expression>) -> Box<Expression> {
     }
 }
 
+/// Fuses the sums of products in a (simplified) expression into [MultiplyAdd](ExpressionContent::MultiplyAdd) nodes
+///
+/// The products accumulate one term after another (`((a * b + c * d) + e * f) + ...`),
+/// so that each addition becomes a fused multiply-add on targets which support it.
+pub fn fuse_multiply_add<'a>(expression: Expression<'a>) -> Expression<'a> {
+    let fuse = |expression: Box<Expression<'a>>| fuse_multiply_add(*expression);
+    let content = match expression.content {
+        ExpressionContent::Add(a, b) => match (fuse(a), fuse(b)) {
+            (
+                c,
+                Expression {
+                    content: ExpressionContent::Multiply(x, y),
+                    ..
+                },
+            )
+            | (
+                Expression {
+                    content: ExpressionContent::Multiply(x, y),
+                    ..
+                },
+                c,
+            ) => ExpressionContent::MultiplyAdd(x, y, Box::new(c)),
+            (a, b) => ExpressionContent::Add(Box::new(a), Box::new(b)),
+        },
+        ExpressionContent::Subtract(a, b) => ExpressionContent::Subtract(Box::new(fuse(a)), Box::new(fuse(b))),
+        ExpressionContent::Multiply(a, b) => ExpressionContent::Multiply(Box::new(fuse(a)), Box::new(fuse(b))),
+        ExpressionContent::Divide(a, b) => ExpressionContent::Divide(Box::new(fuse(a)), Box::new(fuse(b))),
+        content => content,
+    };
+    Expression {
+        size: expression.size,
+        content,
+    }
+}
+
+/// Polynomial in scalar variables, which maps each monomial (the sorted indices of its variables) to its coefficient
+type Polynomial = std::collections::BTreeMap<Vec<usize>, isize>;
+
+/// Multi vector with polynomial coefficients, which maps the index of each basis element (with a positive sign) to its coefficient
+type PolynomialMultiVector = std::collections::BTreeMap<BasisElementIndex, Polynomial>;
+
+/// Names of the variables which hold the quadvector part of the square of a bivector (at most five elements in five dimensions)
+const WEDGE_NAMES: &[&str] = &["wedge0", "wedge1", "wedge2", "wedge3", "wedge4"];
+
+/// Names of the variables which hold the quadvector part of the squared magnitude of a rotor
+const QUADVECTOR_NAMES: &[&str] = &["quadvector0", "quadvector1", "quadvector2", "quadvector3", "quadvector4"];
+
+fn polynomial_add(a: &mut Polynomial, b: &Polynomial, factor: isize) {
+    for (monomial, coefficient) in b.iter() {
+        let sum = a.entry(monomial.clone()).or_insert(0);
+        *sum += factor * coefficient;
+        if *sum == 0 {
+            a.remove(monomial);
+        }
+    }
+}
+
+fn polynomial_product(a: &Polynomial, b: &Polynomial) -> Polynomial {
+    let mut result = Polynomial::new();
+    for (monomial_a, coefficient_a) in a.iter() {
+        for (monomial_b, coefficient_b) in b.iter() {
+            let mut monomial = monomial_a.iter().chain(monomial_b.iter()).cloned().collect::<Vec<_>>();
+            monomial.sort_unstable();
+            polynomial_add(&mut result, &std::iter::once((monomial, *coefficient_a)).collect(), *coefficient_b);
+        }
+    }
+    result
+}
+
+/// Polynomial consisting of a single variable
+fn polynomial_variable(variable: usize) -> Polynomial {
+    std::iter::once((vec![variable], 1)).collect()
+}
+
+/// Polynomial consisting of a single constant
+fn polynomial_constant(value: isize) -> Polynomial {
+    std::iter::once((vec![], value)).collect()
+}
+
+fn multi_vector_add(a: &mut PolynomialMultiVector, b: &PolynomialMultiVector, factor: &Polynomial) {
+    for (index, polynomial) in b.iter() {
+        let sum = a.entry(*index).or_default();
+        polynomial_add(sum, &polynomial_product(polynomial, factor), 1);
+        if sum.is_empty() {
+            a.remove(index);
+        }
+    }
+}
+
+fn multi_vector_geometric_product(a: &PolynomialMultiVector, b: &PolynomialMultiVector, algebra: &GeometricAlgebra) -> PolynomialMultiVector {
+    let mut result = PolynomialMultiVector::new();
+    for (index_a, polynomial_a) in a.iter() {
+        for (index_b, polynomial_b) in b.iter() {
+            let product = BasisElement::product(&BasisElement::from_index(*index_a), &BasisElement::from_index(*index_b), algebra);
+            if product.scalar != 0 {
+                let term = std::iter::once((product.index, polynomial_product(polynomial_a, polynomial_b))).collect();
+                multi_vector_add(&mut result, &term, &polynomial_constant(product.scalar));
+            }
+        }
+    }
+    result
+}
+
+fn multi_vector_grade(a: &PolynomialMultiVector, grade: usize) -> PolynomialMultiVector {
+    a.iter()
+        .filter(|(index, _polynomial)| BasisElement::from_index(**index).grade() == grade)
+        .map(|(index, polynomial)| (*index, polynomial.clone()))
+        .collect()
+}
+
+/// The elements of the given grade of `parameter`, with the variable index of each element being its index in the flat basis
+fn multi_vector_of_parameter(flat_basis: &[BasisElement], grade: usize) -> PolynomialMultiVector {
+    flat_basis
+        .iter()
+        .enumerate()
+        .filter(|(_variable, element)| element.grade() == grade)
+        .map(|(variable, element)| (element.index, std::iter::once((vec![variable], element.scalar)).collect()))
+        .collect()
+}
+
+/// Scalar expressions of the elements of `parameter`, which are the first variables of a polynomial
+fn element_variables<'a>(parameter: &Parameter<'a>) -> Vec<Expression<'a>> {
+    let class = parameter.multi_vector_class();
+    (0..class.flat_basis().len())
+        .map(|index| {
+            let (group_index, index_in_group) = class.index_in_group(index);
+            Expression {
+                size: 1,
+                content: ExpressionContent::Gather(
+                    Box::new(Expression {
+                        size: class.grouped_basis[group_index].len(),
+                        content: ExpressionContent::Variable(parameter.data_type.clone(), parameter.name),
+                    }),
+                    vec![(group_index, index_in_group)],
+                ),
+            }
+        })
+        .collect()
+}
+
+/// Scalar expression of a component of a SIMD vector variable
+fn component_variable<'a>(name: &'static str, size: usize, component: usize) -> Expression<'a> {
+    Expression {
+        size: 1,
+        content: ExpressionContent::Swizzle(
+            Box::new(Expression {
+                size,
+                content: ExpressionContent::Variable(DataType::SimdVector(size), name),
+            }),
+            vec![component],
+        ),
+    }
+}
+
+/// Expresses a polynomial as a sum of products of the given scalar expressions (positive terms first)
+fn polynomial_expression<'a>(polynomial: &Polynomial, variables: &[Expression<'a>]) -> Expression<'a> {
+    let constant = |value: isize| Expression {
+        size: 1,
+        content: ExpressionContent::Constant(DataType::SimdVector(1), vec![value]),
+    };
+    let mut terms = polynomial.iter().collect::<Vec<_>>();
+    terms.sort_by_key(|(_monomial, coefficient)| **coefficient < 0);
+    let mut result: Option<Expression<'a>> = None;
+    for (monomial, coefficient) in terms {
+        let mut factors = monomial.iter().map(|variable| variables[*variable].clone());
+        let mut term = factors.next().unwrap_or_else(|| constant(coefficient.abs()));
+        for factor in factors {
+            term = Expression {
+                size: 1,
+                content: ExpressionContent::Multiply(Box::new(term), Box::new(factor)),
+            };
+        }
+        if !monomial.is_empty() && coefficient.abs() != 1 {
+            term = Expression {
+                size: 1,
+                content: ExpressionContent::Multiply(Box::new(term), Box::new(constant(coefficient.abs()))),
+            };
+        }
+        let content = match result.take() {
+            None if *coefficient > 0 => term.content,
+            None => ExpressionContent::Subtract(Box::new(constant(0)), Box::new(term)),
+            Some(sum) if *coefficient > 0 => ExpressionContent::Add(Box::new(sum), Box::new(term)),
+            Some(sum) => ExpressionContent::Subtract(Box::new(sum), Box::new(term)),
+        };
+        result = Some(Expression { size: 1, content });
+    }
+    result.unwrap_or_else(|| constant(0))
+}
+
+/// Statement which assigns a new scalar variable
+fn scalar_assignment<'a>(name: &'static str, expression: Expression<'a>) -> AstNode<'a> {
+    AstNode::VariableAssignment {
+        name,
+        data_type: Some(DataType::SimdVector(1)),
+        expression: Box::new(expression),
+    }
+}
+
+/// Statement which returns a multi vector, constructed from its elements as polynomials
+fn polynomial_return_statement<'a>(class: &'a MultiVectorClass, result: &PolynomialMultiVector, variables: &[Expression<'a>]) -> AstNode<'a> {
+    let arguments = class
+        .flat_basis()
+        .iter()
+        .map(|element| {
+            let mut polynomial = Polynomial::new();
+            polynomial_add(&mut polynomial, &result[&element.index], element.scalar);
+            (DataType::SimdVector(1), polynomial_expression(&polynomial, variables))
+        })
+        .collect();
+    AstNode::ReturnStatement {
+        expression: Box::new(Expression {
+            size: 1,
+            content: ExpressionContent::InvokeClassMethod(class, "New", arguments),
+        }),
+    }
+}
+
+/// Reverses the order of the generators in each basis element, which negates the grades 2 and 3 (modulo 4)
+fn multi_vector_reversal(a: &PolynomialMultiVector) -> PolynomialMultiVector {
+    a.iter()
+        .map(|(index, polynomial)| {
+            let mut result = Polynomial::new();
+            polynomial_add(
+                &mut result,
+                polynomial,
+                if BasisElement::from_index(*index).grade() % 4 < 2 { 1 } else { -1 },
+            );
+            (*index, result)
+        })
+        .collect()
+}
+
+/// Divides the rotor `x` by the square root of `scale * x * ~x`, which has to consist of a scalar and a quadvector `Q` squaring to a scalar
+///
+/// Pushes the statements computing the coefficients of `BivectorSplit::inverse_sqrt_coefficients` to `body` and returns `(f0 + f1 * Q) * x`.
+/// Multiplying from the left makes the result `y` satisfy `scale * y * ~y = 1` even if `Q` does not commute with `x`, as it does in five dimensions.
+fn normalized_rotor<'a>(
+    x: &PolynomialMultiVector,
+    scale: &Polynomial,
+    variables: &mut Vec<Expression<'a>>,
+    body: &mut Vec<AstNode<'a>>,
+    algebra: &GeometricAlgebra,
+) -> Option<PolynomialMultiVector> {
+    let mut squared_magnitude = PolynomialMultiVector::new();
+    multi_vector_add(
+        &mut squared_magnitude,
+        &multi_vector_geometric_product(x, &multi_vector_reversal(x), algebra),
+        scale,
+    );
+    let quadvector = multi_vector_grade(&squared_magnitude, 4);
+    if squared_magnitude.keys().any(|index| *index != 0 && !quadvector.contains_key(index)) || quadvector.len() > QUADVECTOR_NAMES.len() {
+        return None;
+    }
+    body.push(scalar_assignment(
+        "squared_magnitude",
+        polynomial_expression(&squared_magnitude.get(&0).cloned().unwrap_or_default(), variables),
+    ));
+    let mut quadvector_variables = PolynomialMultiVector::new();
+    for ((index, polynomial), name) in quadvector.iter().zip(QUADVECTOR_NAMES.iter()) {
+        body.push(scalar_assignment(name, polynomial_expression(polynomial, variables)));
+        quadvector_variables.insert(*index, polynomial_variable(variables.len()));
+        variables.push(Expression {
+            size: 1,
+            content: ExpressionContent::Variable(DataType::SimdVector(1), name),
+        });
+    }
+    let quadvector_square = multi_vector_geometric_product(&quadvector_variables, &quadvector_variables, algebra);
+    if quadvector_square.keys().any(|index| *index != 0) {
+        return None;
+    }
+    body.push(AstNode::VariableAssignment {
+        name: "coefficients",
+        data_type: Some(DataType::SimdVector(4)),
+        expression: Box::new(Expression {
+            size: 4,
+            content: ExpressionContent::InvokeInstanceMethod(
+                DataType::SimdVector(1),
+                Box::new(Expression {
+                    size: 1,
+                    content: ExpressionContent::Variable(DataType::SimdVector(1), "squared_magnitude"),
+                }),
+                "InverseSqrtCoefficients",
+                DataType::SimdVector(4),
+                vec![(
+                    DataType::SimdVector(1),
+                    polynomial_expression(&quadvector_square.get(&0).cloned().unwrap_or_default(), variables),
+                )],
+            ),
+        }),
+    });
+    let coefficients = (0..2)
+        .map(|component| {
+            variables.push(component_variable("coefficients", 4, component));
+            polynomial_variable(variables.len() - 1)
+        })
+        .collect::<Vec<_>>();
+    let mut result = PolynomialMultiVector::new();
+    multi_vector_add(&mut result, x, &coefficients[0]);
+    multi_vector_add(
+        &mut result,
+        &multi_vector_geometric_product(&quadvector_variables, x, algebra),
+        &coefficients[1],
+    );
+    Some(result)
+}
+
 impl MultiVectorClass {
     pub fn flat_basis(&self) -> Vec<BasisElement> {
         self.grouped_basis.iter().flatten().cloned().collect()
@@ -156,6 +462,37 @@ impl MultiVectorClass {
         signature
     }
 
+    /// Number of scalars a group of the given size occupies on targets with SIMD
+    ///
+    /// Groups of two to four elements are padded to a SIMD vector of four scalars, groups of eight elements fill a vector of eight scalars.
+    pub fn simd_width(group_size: usize) -> usize {
+        match group_size {
+            1 => 1,
+            2..=4 => 4,
+            _ => 8,
+        }
+    }
+
+    /// Padding of the class in memory (in scalars before and after each group, and at the end) on targets with SIMD
+    ///
+    /// Groups of more than one element occupy a whole SIMD vector (see [simd_width](Self::simd_width)), which is aligned to its size.
+    pub fn padding(&self) -> (Vec<(usize, usize)>, usize) {
+        let mut offset = 0;
+        let mut alignment = 1;
+        let padding = self
+            .grouped_basis
+            .iter()
+            .map(|group| {
+                let simd_width = Self::simd_width(group.len());
+                let before = (simd_width - offset % simd_width) % simd_width;
+                offset += before + simd_width;
+                alignment = alignment.max(simd_width);
+                (before, simd_width - group.len())
+            })
+            .collect();
+        (padding, (alignment - offset % alignment) % alignment)
+    }
+
     pub fn index_in_group(&self, mut index: usize) -> (usize, usize) {
         for (group_index, group) in self.grouped_basis.iter().enumerate() {
             if index >= group.len() {
@@ -186,7 +523,10 @@ impl MultiVectorClass {
                         .collect(),
                 ),
             };
-            body.push((DataType::SimdVector(size), *simplify_and_legalize(Box::new(expression))));
+            body.push((
+                DataType::SimdVector(size),
+                fuse_multiply_add(*simplify_and_legalize(Box::new(expression))),
+            ));
         }
         AstNode::TraitImplementation {
             result: Parameter {
@@ -203,12 +543,15 @@ impl MultiVectorClass {
         }
     }
 
+    /// Applies the involution to the class of `parameter_a`
+    ///
+    /// Projections (conversions) pass the class they project into as `target`, which is otherwise looked up by the signature of the result.
     pub fn involution<'a>(
         name: &'static str,
         involution: &Involution,
         parameter_a: &Parameter<'a>,
         registry: &'a MultiVectorClassRegistry,
-        project: bool,
+        target: Option<&'a MultiVectorClass>,
     ) -> AstNode<'a> {
         let a_flat_basis = parameter_a.multi_vector_class().flat_basis();
         let mut result_signature = Vec::new();
@@ -220,15 +563,22 @@ impl MultiVectorClass {
                 }
             }
         }
-        if project {
+        if target.is_some() {
             for (in_element, _out_element) in involution.terms.iter() {
                 if !a_flat_basis.iter().any(|element| element.index == in_element.index) {
                     return AstNode::None;
                 }
             }
         }
+        if involution
+            .terms
+            .iter()
+            .all(|(in_element, out_element)| out_element.scalar == 0 || !a_flat_basis.iter().any(|element| element.index == in_element.index))
+        {
+            return AstNode::None; // Masks which set all elements of the class to zero
+        }
         result_signature.sort_unstable();
-        if let Some(result_class) = registry.get(&result_signature) {
+        if let Some(result_class) = target.or_else(|| registry.get(&result_signature)) {
             let result_flat_basis = result_class.flat_basis();
             let mut body = Vec::new();
             let mut base_index = 0;
@@ -270,7 +620,14 @@ impl MultiVectorClass {
                         }),
                     ),
                 };
-                body.push((DataType::SimdVector(size), *simplify_and_legalize(Box::new(expression))));
+                let mut expression = *simplify_and_legalize(Box::new(expression));
+                if expression.content == ExpressionContent::None {
+                    expression = Expression {
+                        size,
+                        content: ExpressionContent::Constant(DataType::SimdVector(size), vec![0]),
+                    };
+                }
+                body.push((DataType::SimdVector(size), fuse_multiply_add(expression)));
                 base_index += size;
             }
             AstNode::TraitImplementation {
@@ -573,7 +930,10 @@ impl MultiVectorClass {
                         content: ExpressionContent::Constant(DataType::SimdVector(size), (0..size).map(|_| 0).collect()),
                     };
                 }
-                body.push((DataType::SimdVector(size), *simplify_and_legalize(Box::new(expression))));
+                body.push((
+                    DataType::SimdVector(size),
+                    fuse_multiply_add(*simplify_and_legalize(Box::new(expression))),
+                ));
                 base_index += size;
             }
             if body.is_empty() {
@@ -737,6 +1097,63 @@ impl MultiVectorClass {
         }
     }
 
+    /// Derives the norm of the bulk (elements containing a degenerate generator) or of the weight (all other elements)
+    ///
+    /// The degenerate generators are left out of the elements of the bulk, so that it is measured by the metric of the remaining generators.
+    /// This way the bulk norm of an ideal point in PGA is its length, while its magnitude (which is the weight norm) is zero.
+    pub fn derive_degenerate_norm<'a>(
+        name: &'static str,
+        bulk: bool,
+        parameter_a: &Parameter<'a>,
+        registry: &'a MultiVectorClassRegistry,
+        algebra: &GeometricAlgebra,
+    ) -> AstNode<'a> {
+        let scalar_class = match registry.get(&[0]) {
+            Some(scalar_class) => scalar_class,
+            None => return AstNode::None,
+        };
+        let mut squared_norm = Polynomial::new();
+        for (variable, element) in parameter_a.multi_vector_class().flat_basis().iter().enumerate() {
+            if algebra.is_degenerate(element) == bulk {
+                let square = element
+                    .component_bits()
+                    .map(|bit| algebra.generator_squares[bit])
+                    .filter(|square| *square != 0)
+                    .product();
+                polynomial_add(&mut squared_norm, &std::iter::once((vec![variable, variable], 1)).collect(), square);
+            }
+        }
+        if squared_norm.is_empty() {
+            return AstNode::None;
+        }
+        AstNode::TraitImplementation {
+            result: Parameter {
+                name,
+                data_type: DataType::MultiVector(scalar_class),
+            },
+            parameters: vec![parameter_a.clone()],
+            body: vec![AstNode::ReturnStatement {
+                expression: Box::new(Expression {
+                    size: 1,
+                    content: ExpressionContent::InvokeClassMethod(
+                        scalar_class,
+                        "Constructor",
+                        vec![(
+                            DataType::SimdVector(1),
+                            Expression {
+                                size: 1,
+                                content: ExpressionContent::SquareRoot(Box::new(polynomial_expression(
+                                    &squared_norm,
+                                    &element_variables(parameter_a),
+                                ))),
+                            },
+                        )],
+                    ),
+                }),
+            }],
+        }
+    }
+
     pub fn derive_signum<'a>(
         name: &'static str,
         geometric_product: &AstNode<'a>,
@@ -1260,4 +1677,389 @@ impl MultiVectorClass {
             }],
         }
     }
+
+    /// Derives the exponential of a bivector (optionally with a scalar part) in closed form
+    ///
+    /// The square of the bivector `B * B = inner + wedge` splits into a scalar `inner` and a quadvector `wedge`,
+    /// so that `exp(B) = c0 + c1 * wedge + s0 * B + s1 * wedge * B` with the coefficients of `BivectorSplit::exp_coefficients`.
+    /// This requires `wedge` to square to a scalar, which is the case in up to five dimensions.
+    pub fn derive_exponential<'a>(
+        name: &'static str,
+        parameter_a: &Parameter<'a>,
+        registry: &'a MultiVectorClassRegistry,
+        algebra: &GeometricAlgebra,
+    ) -> AstNode<'a> {
+        let a_flat_basis = parameter_a.multi_vector_class().flat_basis();
+        if a_flat_basis.iter().any(|element| element.grade() != 0 && element.grade() != 2) || !a_flat_basis.iter().any(|element| element.grade() == 2)
+        {
+            return AstNode::None;
+        }
+        let mut variables = element_variables(parameter_a);
+        let mut body = Vec::new();
+        let bivector = multi_vector_of_parameter(&a_flat_basis, 2);
+        let square = multi_vector_geometric_product(&bivector, &bivector, algebra);
+        let inner = square.get(&0).cloned().unwrap_or_default();
+        let wedge = multi_vector_grade(&square, 4);
+        let mut wedge_variables = PolynomialMultiVector::new();
+        let mut coefficients = [polynomial_constant(1), Polynomial::new(), polynomial_constant(1), Polynomial::new()];
+        if !inner.is_empty() || !wedge.is_empty() {
+            if wedge.len() > WEDGE_NAMES.len() {
+                return AstNode::None;
+            }
+            body.push(scalar_assignment("inner", polynomial_expression(&inner, &variables)));
+            for ((index, polynomial), name) in wedge.iter().zip(WEDGE_NAMES.iter()) {
+                body.push(scalar_assignment(name, polynomial_expression(polynomial, &variables)));
+                wedge_variables.insert(*index, polynomial_variable(variables.len()));
+                variables.push(Expression {
+                    size: 1,
+                    content: ExpressionContent::Variable(DataType::SimdVector(1), name),
+                });
+            }
+            let wedge_square = multi_vector_geometric_product(&wedge_variables, &wedge_variables, algebra);
+            if wedge_square.keys().any(|index| *index != 0) {
+                return AstNode::None;
+            }
+            body.push(AstNode::VariableAssignment {
+                name: "coefficients",
+                data_type: Some(DataType::SimdVector(4)),
+                expression: Box::new(Expression {
+                    size: 4,
+                    content: ExpressionContent::InvokeInstanceMethod(
+                        DataType::SimdVector(1),
+                        Box::new(Expression {
+                            size: 1,
+                            content: ExpressionContent::Variable(DataType::SimdVector(1), "inner"),
+                        }),
+                        "ExpCoefficients",
+                        DataType::SimdVector(4),
+                        vec![(
+                            DataType::SimdVector(1),
+                            polynomial_expression(&wedge_square.get(&0).cloned().unwrap_or_default(), &variables),
+                        )],
+                    ),
+                }),
+            });
+            for (component, coefficient) in coefficients.iter_mut().enumerate() {
+                *coefficient = polynomial_variable(variables.len());
+                variables.push(component_variable("coefficients", 4, component));
+            }
+        }
+        if let Some(scalar_index) = a_flat_basis.iter().position(|element| element.index == 0) {
+            body.push(scalar_assignment(
+                "scale",
+                Expression {
+                    size: 1,
+                    content: ExpressionContent::InvokeInstanceMethod(
+                        DataType::SimdVector(1),
+                        Box::new(variables[scalar_index].clone()),
+                        "Exp",
+                        DataType::SimdVector(1),
+                        vec![],
+                    ),
+                },
+            ));
+            let scale = polynomial_variable(variables.len());
+            variables.push(Expression {
+                size: 1,
+                content: ExpressionContent::Variable(DataType::SimdVector(1), "scale"),
+            });
+            for coefficient in coefficients.iter_mut() {
+                *coefficient = polynomial_product(coefficient, &scale);
+            }
+        }
+        let mut result = PolynomialMultiVector::new();
+        multi_vector_add(&mut result, &std::iter::once((0, polynomial_constant(1))).collect(), &coefficients[0]);
+        multi_vector_add(&mut result, &wedge_variables, &coefficients[1]);
+        multi_vector_add(&mut result, &bivector, &coefficients[2]);
+        let wedge_bivector = multi_vector_geometric_product(&wedge_variables, &bivector, algebra);
+        multi_vector_add(&mut result, &multi_vector_grade(&wedge_bivector, 2), &coefficients[3]);
+        let result_signature = result.keys().cloned().collect::<Vec<_>>();
+        if let Some(result_class) = registry.get(&result_signature) {
+            body.push(polynomial_return_statement(result_class, &result, &variables));
+            AstNode::TraitImplementation {
+                result: Parameter {
+                    name,
+                    data_type: DataType::MultiVector(result_class),
+                },
+                parameters: vec![parameter_a.clone()],
+                body,
+            }
+        } else {
+            AstNode::None
+        }
+    }
+
+    /// Derives the logarithm of a rotor (scalar, bivector and quadvector parts) in closed form, the inverse of [derive_exponential](Self::derive_exponential)
+    ///
+    /// The bivector part `B` and quadvector part `Q` result in `ln(R) = ln(m) + k1 * B + k2 * Q * B`
+    /// with the magnitude `m` and the coefficients of `BivectorSplit::ln_coefficients`.
+    /// The scalar part `ln(m)` is only kept if there is no class of just the bivector part.
+    pub fn derive_logarithm<'a>(
+        name: &'static str,
+        magnitude: &AstNode<'a>,
+        parameter_a: &Parameter<'a>,
+        registry: &'a MultiVectorClassRegistry,
+        algebra: &GeometricAlgebra,
+    ) -> AstNode<'a> {
+        let magnitude_result = result_of_trait!(magnitude);
+        let a_flat_basis = parameter_a.multi_vector_class().flat_basis();
+        let scalar_index = match a_flat_basis.iter().position(|element| element.index == 0) {
+            Some(scalar_index) => scalar_index,
+            None => return AstNode::None,
+        };
+        if a_flat_basis.iter().any(|element| element.grade() % 2 == 1 || element.grade() > 4)
+            || !a_flat_basis.iter().any(|element| element.grade() == 2)
+        {
+            return AstNode::None;
+        }
+        let mut variables = element_variables(parameter_a);
+        let bivector = multi_vector_of_parameter(&a_flat_basis, 2);
+        let quadvector = multi_vector_of_parameter(&a_flat_basis, 4);
+        let quadvector_square = multi_vector_geometric_product(&quadvector, &quadvector, algebra);
+        if quadvector_square.keys().any(|index| *index != 0) {
+            return AstNode::None;
+        }
+        let mut body = vec![scalar_assignment(
+            "magnitude",
+            Expression {
+                size: 1,
+                content: ExpressionContent::Access(
+                    Box::new(Expression {
+                        size: 1,
+                        content: ExpressionContent::InvokeInstanceMethod(
+                            parameter_a.data_type.clone(),
+                            Box::new(Expression {
+                                size: 1,
+                                content: ExpressionContent::Variable(parameter_a.data_type.clone(), parameter_a.name),
+                            }),
+                            magnitude_result.name,
+                            magnitude_result.data_type.clone(),
+                            vec![],
+                        ),
+                    }),
+                    0,
+                ),
+            },
+        )];
+        body.push(AstNode::VariableAssignment {
+            name: "coefficients",
+            data_type: Some(DataType::SimdVector(4)),
+            expression: Box::new(Expression {
+                size: 4,
+                content: ExpressionContent::InvokeInstanceMethod(
+                    DataType::SimdVector(1),
+                    Box::new(Expression {
+                        size: 1,
+                        content: ExpressionContent::Variable(DataType::SimdVector(1), "magnitude"),
+                    }),
+                    "LnCoefficients",
+                    DataType::SimdVector(4),
+                    vec![
+                        (DataType::SimdVector(1), variables[scalar_index].clone()),
+                        (
+                            DataType::SimdVector(1),
+                            polynomial_expression(&quadvector_square.get(&0).cloned().unwrap_or_default(), &variables),
+                        ),
+                    ],
+                ),
+            }),
+        });
+        let coefficients = (0..3)
+            .map(|component| {
+                variables.push(component_variable("coefficients", 4, component));
+                polynomial_variable(variables.len() - 1)
+            })
+            .collect::<Vec<_>>();
+        let mut result = PolynomialMultiVector::new();
+        multi_vector_add(&mut result, &bivector, &coefficients[1]);
+        let quadvector_bivector = multi_vector_geometric_product(&quadvector, &bivector, algebra);
+        multi_vector_add(&mut result, &multi_vector_grade(&quadvector_bivector, 2), &coefficients[2]);
+        let mut result_class = registry.get(&result.keys().cloned().collect::<Vec<_>>());
+        if result_class.is_none() {
+            result.insert(0, coefficients[0].clone());
+            result_class = registry.get(&result.keys().cloned().collect::<Vec<_>>());
+        }
+        if let Some(result_class) = result_class {
+            body.push(polynomial_return_statement(result_class, &result, &variables));
+            AstNode::TraitImplementation {
+                result: Parameter {
+                    name,
+                    data_type: DataType::MultiVector(result_class),
+                },
+                parameters: vec![parameter_a.clone()],
+                body,
+            }
+        } else {
+            AstNode::None
+        }
+    }
+
+    /// Derives the power of a floating point exponent as `exp(ln(self) * exponent)`
+    pub fn derive_power_of_float<'a>(
+        name: &'static str,
+        geometric_product: &AstNode<'a>,
+        exponential: &AstNode<'a>,
+        logarithm: &AstNode<'a>,
+        parameter_a: &Parameter<'a>,
+        parameter_b: &Parameter<'a>,
+    ) -> AstNode<'a> {
+        let geometric_product_result = result_of_trait!(geometric_product);
+        let exponential_result = result_of_trait!(exponential);
+        let logarithm_result = result_of_trait!(logarithm);
+        AstNode::TraitImplementation {
+            result: Parameter {
+                name,
+                data_type: exponential_result.data_type.clone(),
+            },
+            parameters: vec![parameter_a.clone(), parameter_b.clone()],
+            body: vec![AstNode::ReturnStatement {
+                expression: Box::new(Expression {
+                    size: 1,
+                    content: ExpressionContent::InvokeInstanceMethod(
+                        geometric_product_result.data_type.clone(),
+                        Box::new(Expression {
+                            size: 1,
+                            content: ExpressionContent::InvokeInstanceMethod(
+                                logarithm_result.data_type.clone(),
+                                Box::new(Expression {
+                                    size: 1,
+                                    content: ExpressionContent::InvokeInstanceMethod(
+                                        parameter_a.data_type.clone(),
+                                        Box::new(Expression {
+                                            size: 1,
+                                            content: ExpressionContent::Variable(parameter_a.data_type.clone(), parameter_a.name),
+                                        }),
+                                        logarithm_result.name,
+                                        logarithm_result.data_type.clone(),
+                                        vec![],
+                                    ),
+                                }),
+                                geometric_product_result.name,
+                                geometric_product_result.data_type.clone(),
+                                vec![(
+                                    parameter_b.data_type.clone(),
+                                    Expression {
+                                        size: 1,
+                                        content: ExpressionContent::Variable(parameter_b.data_type.clone(), parameter_b.name),
+                                    },
+                                )],
+                            ),
+                        }),
+                        exponential_result.name,
+                        exponential_result.data_type.clone(),
+                        vec![],
+                    ),
+                }),
+            }],
+        }
+    }
+
+    /// Derives the normalization `self / sqrt(self * ~self)` of a rotor, after which `self * ~self = 1`
+    ///
+    /// Unlike [derive_signum](Self::derive_signum), this also divides out the quadvector part of `self * ~self` in four and five dimensions,
+    /// which enforces the study condition of motors in 3D PGA (their e0123 part would otherwise be ignored).
+    /// The result is of the class of the normalized elements, which in 3D CGA is a `Versor` even for a `Motor`.
+    pub fn derive_normalization<'a>(
+        name: &'static str,
+        parameter_a: &Parameter<'a>,
+        registry: &'a MultiVectorClassRegistry,
+        algebra: &GeometricAlgebra,
+    ) -> AstNode<'a> {
+        Self::derive_rotor_normalization(name, false, parameter_a, registry, algebra)
+    }
+
+    /// Derives the square root of a rotor as `sqrt(m) * (m + self) / |m + self|` with the magnitude `m`, which is halfway between the identity and `self`
+    ///
+    /// Just like in [derive_normalization](Self::derive_normalization), the quadvector part of `(m + self) * ~(m + self)` is divided out as well.
+    /// `m` only includes the scalar part of `self * ~self`, so apart from a positive factor (like the magnitude of a complex number) `self` has to be normalized.
+    /// The square root of the rotor `-1` (or of a negative real number) is undefined.
+    pub fn derive_square_root<'a>(
+        name: &'static str,
+        parameter_a: &Parameter<'a>,
+        registry: &'a MultiVectorClassRegistry,
+        algebra: &GeometricAlgebra,
+    ) -> AstNode<'a> {
+        Self::derive_rotor_normalization(name, true, parameter_a, registry, algebra)
+    }
+
+    /// Normalizes `self` or `m + self` for a class of a scalar, bivectors and optionally quadvectors
+    fn derive_rotor_normalization<'a>(
+        name: &'static str,
+        square_root: bool,
+        parameter_a: &Parameter<'a>,
+        registry: &'a MultiVectorClassRegistry,
+        algebra: &GeometricAlgebra,
+    ) -> AstNode<'a> {
+        let a_flat_basis = parameter_a.multi_vector_class().flat_basis();
+        if !a_flat_basis.iter().any(|element| element.index == 0)
+            || a_flat_basis.iter().any(|element| element.grade() % 2 == 1)
+            || !a_flat_basis.iter().any(|element| element.grade() == 2)
+        {
+            return AstNode::None;
+        }
+        let mut variables = element_variables(parameter_a);
+        let mut body = Vec::new();
+        let mut x = PolynomialMultiVector::new();
+        for grade in (0..=algebra.generator_squares.len()).step_by(2) {
+            multi_vector_add(&mut x, &multi_vector_of_parameter(&a_flat_basis, grade), &polynomial_constant(1));
+        }
+        let mut scale = polynomial_constant(1);
+        if square_root {
+            // With the magnitude m = norm, dividing by the square root of inverse_norm * (m + self) * ~(m + self)
+            // normalizes m + self and multiplies it by sqrt(m) at the same time
+            let squared_norm = multi_vector_geometric_product(&x, &multi_vector_reversal(&x), algebra)
+                .get(&0)
+                .cloned()
+                .unwrap_or_default();
+            body.push(scalar_assignment(
+                "norm",
+                Expression {
+                    size: 1,
+                    content: ExpressionContent::SquareRoot(Box::new(polynomial_expression(&squared_norm, &variables))),
+                },
+            ));
+            variables.push(Expression {
+                size: 1,
+                content: ExpressionContent::Variable(DataType::SimdVector(1), "norm"),
+            });
+            let norm = polynomial_variable(variables.len() - 1);
+            body.push(scalar_assignment(
+                "inverse_norm",
+                Expression {
+                    size: 1,
+                    content: ExpressionContent::Divide(
+                        Box::new(Expression {
+                            size: 1,
+                            content: ExpressionContent::Constant(DataType::SimdVector(1), vec![1]),
+                        }),
+                        Box::new(variables.last().unwrap().clone()),
+                    ),
+                },
+            ));
+            variables.push(Expression {
+                size: 1,
+                content: ExpressionContent::Variable(DataType::SimdVector(1), "inverse_norm"),
+            });
+            scale = polynomial_variable(variables.len() - 1);
+            multi_vector_add(&mut x, &std::iter::once((0, polynomial_constant(1))).collect(), &norm);
+        }
+        let result = match normalized_rotor(&x, &scale, &mut variables, &mut body, algebra) {
+            Some(result) => result,
+            None => return AstNode::None,
+        };
+        match registry.get(&result.keys().cloned().collect::<Vec<_>>()) {
+            Some(result_class) => {
+                body.push(polynomial_return_statement(result_class, &result, &variables));
+                AstNode::TraitImplementation {
+                    result: Parameter {
+                        name,
+                        data_type: DataType::MultiVector(result_class),
+                    },
+                    parameters: vec![parameter_a.clone()],
+                    body,
+                }
+            }
+            _ => AstNode::None,
+        }
+    }
 }
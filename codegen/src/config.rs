@@ -0,0 +1,441 @@
+/// Parser for TOML descriptor files, the readable alternative to the compact descriptor string
+///
+/// ```toml
+/// name = "ppga2d"
+/// scalar_type = "f32" # Optional, defaults to `f32`
+/// generators = [{ name = "e0", square = 0 }, { name = "e1", square = 1 }, { name = "e2", square = 1 }]
+/// traits = ["GeometricProduct", "Transformation"] # Optional, defaults to all traits
+///
+/// [[classes]]
+/// name = "Translator"
+/// doc = "Translation in the plane" # Optional
+/// groups = [["1", "e12", "e01", "-e02"]]
+/// ```
+use crate::{
+    algebra::{BasisElement, BasisElementIndex, GeometricAlgebra, MultiVectorClass},
+    ast::ScalarType,
+    parse::{is_identifier, parse_class, Descriptor, ParseError},
+};
+use serde::Deserialize;
+use toml::Spanned;
+
+/// Traits which can be selected in the `traits` list
+pub const TRAIT_NAMES: &[&str] = &[
+    "Zero",
+    "One",
+    "Neg",
+    "Automorphism",
+    "Reversal",
+    "Conjugation",
+    "Dual",
+    "Bulk",
+    "Weight",
+    "Into",
+    "Add",
+    "Sub",
+    "Mul",
+    "Div",
+    "GeometricProduct",
+    "RegressiveProduct",
+    "OuterProduct",
+    "InnerProduct",
+    "LeftContraction",
+    "RightContraction",
+    "ScalarProduct",
+    "SquaredMagnitude",
+    "Magnitude",
+    "BulkNorm",
+    "WeightNorm",
+    "IdealNorm",
+    "Signum",
+    "Normalize",
+    "Inverse",
+    "Powi",
+    "Exp",
+    "Ln",
+    "Powf",
+    "Sqrt",
+    "GeometricQuotient",
+    "Transformation",
+];
+
+/// Traits which are derived from other traits and call them in their generated implementations
+///
+/// All other traits are self-contained, e.g. `Exp`, `Sqrt`, `Normalize`, `Bulk`, `Weight` and the norms are closed-form polynomials
+/// (which call no more than the scalar functions of `BivectorSplit`), so they need no entry.
+const TRAIT_DEPENDENCIES: &[(&str, &[&str])] = &[
+    ("SquaredMagnitude", &["ScalarProduct", "Reversal"]),
+    ("Magnitude", &["SquaredMagnitude"]),
+    ("Mul", &["GeometricProduct"]),
+    ("Signum", &["GeometricProduct", "Magnitude"]),
+    ("Inverse", &["GeometricProduct", "SquaredMagnitude", "Reversal"]),
+    ("Powi", &["GeometricProduct", "One", "Inverse"]),
+    ("GeometricQuotient", &["GeometricProduct", "Inverse"]),
+    ("Transformation", &["GeometricProduct", "Reversal", "Into"]),
+    ("Ln", &["Magnitude"]),
+    ("Powf", &["GeometricProduct", "Exp", "Ln"]),
+];
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AlgebraConfig {
+    name: Spanned<String>,
+    scalar_type: Option<Spanned<String>>,
+    generators: Spanned<Vec<GeneratorConfig>>,
+    traits: Option<Vec<Spanned<String>>>,
+    #[serde(default)]
+    classes: Vec<ClassConfig>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GeneratorConfig {
+    name: Spanned<String>,
+    square: Spanned<isize>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ClassConfig {
+    name: Spanned<String>,
+    doc: Option<String>,
+    groups: Vec<Spanned<Vec<Spanned<String>>>>,
+}
+
+/// Byte offset and content of a string value, skipping the opening quote
+///
+/// Strings whose content does not appear verbatim (multi-line strings or escape sequences) start at the opening quote instead.
+fn string_token(value: &Spanned<String>) -> (usize, &str) {
+    let offset = verbatim_span(value).map_or(value.span().start, |span| span.start);
+    (offset, value.get_ref().as_str())
+}
+
+/// Span of the content of a string value, if it appears verbatim between the quotes
+fn verbatim_span(value: &Spanned<String>) -> Option<std::ops::Range<usize>> {
+    let span = value.span();
+    if span.len() == value.get_ref().len() + 2 {
+        Some(span.start + 1..span.end - 1)
+    } else {
+        None
+    }
+}
+
+/// Spans of all string values which do not appear verbatim, so that errors underline the whole string instead of its content
+fn escaped_spans(config: &AlgebraConfig) -> Vec<std::ops::Range<usize>> {
+    std::iter::once(&config.name)
+        .chain(config.scalar_type.iter())
+        .chain(config.generators.get_ref().iter().map(|generator| &generator.name))
+        .chain(config.traits.iter().flatten())
+        .chain(
+            config
+                .classes
+                .iter()
+                .flat_map(|class| std::iter::once(&class.name).chain(class.groups.iter().flat_map(|group| group.get_ref().iter()))),
+        )
+        .filter(|value| verbatim_span(value).is_none())
+        .map(|value| value.span())
+        .collect()
+}
+
+/// Parses a basis element, either in the compact notation (`-e013`) or as a `^`-separated product of generator names (`-e0^e1^e3`)
+fn parse_element(name: &str, generator_names: &[&str], algebra: &GeometricAlgebra) -> Result<BasisElement, String> {
+    let (sign, product) = match name.strip_prefix('-') {
+        Some(product) => (-1, product),
+        None => (1, name),
+    };
+    if !product.contains('^') && !generator_names.contains(&product) {
+        return BasisElement::parse(name, algebra);
+    }
+    let mut element = BasisElement::from_index(0);
+    element.scalar = sign;
+    for generator_name in product.split('^') {
+        let index = generator_names
+            .iter()
+            .position(|other| *other == generator_name)
+            .ok_or_else(|| format!("`{}` is not the name of a generator", generator_name))?;
+        if element.index & (1 << index) != 0 {
+            return Err(format!("generator `{}` occurs more than once", generator_name));
+        }
+        element = BasisElement::product(&element, &BasisElement::from_index(1 << index), algebra);
+    }
+    Ok(element)
+}
+
+/// Parses the contents of a TOML descriptor file into a [Descriptor]
+pub fn parse_config(origin: &str, text: &str) -> Result<Descriptor, ParseError> {
+    let error = |offset: usize, token: &str, message: String| ParseError {
+        origin: origin.to_string(),
+        text: text.to_string(),
+        offset,
+        token: token.to_string(),
+        message,
+    };
+    let config: AlgebraConfig = toml::from_str(text).map_err(|toml_error| {
+        let span = toml_error.span().unwrap_or(0..0);
+        error(span.start, &text[span.clone()], toml_error.message().to_string())
+    })?;
+    let escaped_spans = escaped_spans(&config);
+    let error = |offset: usize, token: &str, message: String| match escaped_spans.iter().find(|span| span.start == offset) {
+        Some(span) => error(offset, &text[span.clone()], message),
+        None => error(offset, token, message),
+    };
+
+    // Validate the algebra name and scalar type
+    let (name_offset, algebra_name) = string_token(&config.name);
+    if !is_identifier(algebra_name) {
+        return Err(error(name_offset, algebra_name, "expected an algebra name".to_string()));
+    }
+    let scalar_type = match &config.scalar_type {
+        Some(scalar_type) => {
+            let (offset, name) = string_token(scalar_type);
+            ScalarType::parse(name).ok_or_else(|| error(offset, name, "expected a scalar type of `f32` or `f64`".to_string()))?
+        }
+        None => ScalarType::F32,
+    };
+
+    // Validate the named generators
+    let generators = config.generators.get_ref();
+    if generators.len() > std::mem::size_of::<BasisElementIndex>() * 8 {
+        let span = config.generators.span();
+        return Err(error(
+            span.start,
+            &text[span],
+            format!("expected at most 16 generators, found {}", generators.len()),
+        ));
+    }
+    let mut generator_names = Vec::new();
+    let mut generator_squares = Vec::new();
+    for generator in generators.iter() {
+        let (offset, name) = string_token(&generator.name);
+        if !is_identifier(name) {
+            return Err(error(offset, name, "expected a generator name".to_string()));
+        }
+        if generator_names.contains(&name) {
+            return Err(error(offset, name, format!("generator `{}` is defined more than once", name)));
+        }
+        let square = *generator.square.get_ref();
+        if !(-1..=1).contains(&square) {
+            let span = generator.square.span();
+            return Err(error(
+                span.start,
+                &text[span],
+                "expected a generator square of `-1`, `0` or `1`".to_string(),
+            ));
+        }
+        generator_names.push(name);
+        generator_squares.push(square);
+    }
+
+    // Validate the selected traits and add the traits they are derived from
+    let traits = match &config.traits {
+        Some(trait_names) => {
+            let mut traits = std::collections::BTreeSet::new();
+            for trait_name in trait_names.iter() {
+                let (offset, name) = string_token(trait_name);
+                if !TRAIT_NAMES.contains(&name) {
+                    return Err(error(offset, name, format!("`{}` is not the name of a generated trait", name)));
+                }
+                traits.insert(name.to_string());
+            }
+            for (name, dependencies) in TRAIT_DEPENDENCIES.iter().rev() {
+                if traits.contains(*name) {
+                    traits.extend(dependencies.iter().map(|dependency| dependency.to_string()));
+                }
+            }
+            Some(traits)
+        }
+        None => None,
+    };
+
+    // Parse the user-defined multivector classes
+    let algebra = GeometricAlgebra {
+        generator_squares: generator_squares.as_slice(),
+    };
+    let mut classes: Vec<MultiVectorClass> = Vec::new();
+    for class_config in config.classes.iter() {
+        let groups = class_config
+            .groups
+            .iter()
+            .map(|group| {
+                let span = group.span();
+                ((span.start, &text[span]), group.get_ref().iter().map(string_token).collect())
+            })
+            .collect::<Vec<_>>();
+        let class = parse_class(
+            &classes,
+            string_token(&class_config.name),
+            class_config.doc.clone(),
+            &groups,
+            |element_name| parse_element(element_name, &generator_names, &algebra),
+            &error,
+        )?;
+        classes.push(class);
+    }
+
+    Ok(Descriptor {
+        algebra_name: algebra_name.to_string(),
+        generator_squares,
+        scalar_type,
+        traits,
+        classes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GENERATORS: &str = "name = \"ppga3d\"
+generators = [
+    { name = \"e0\", square = 0 },
+    { name = \"e1\", square = 1 },
+    { name = \"e2\", square = 1 },
+    { name = \"e3\", square = 1 },
+]
+";
+
+    fn config_error(text: &str) -> ParseError {
+        match parse_config("algebra.toml", text) {
+            Ok(_) => panic!("`{}` should not parse", text),
+            Err(error) => error,
+        }
+    }
+
+    #[test]
+    fn bad_generator_square() {
+        let text = GENERATORS.replace("{ name = \"e2\", square = 1 }", "{ name = \"e2\", square = 2 }");
+        let error = config_error(&text);
+        assert_eq!((error.offset, error.token.as_str()), (text.find("2 }").unwrap(), "2"));
+        assert_eq!(
+            error.to_string(),
+            "error: expected a generator square of `-1`, `0` or `1`\n  \
+             --> algebra.toml:5:29\n   \
+             |     { name = \"e2\", square = 2 },\n   \
+             |                             ^"
+        );
+    }
+
+    #[test]
+    fn duplicate_generator() {
+        let text = GENERATORS.replace("{ name = \"e3\"", "{ name = \"e1\"");
+        let error = config_error(&text);
+        assert_eq!((error.offset, error.token.as_str()), (text.rfind("e1").unwrap(), "e1"));
+        assert_eq!(error.message, "generator `e1` is defined more than once");
+    }
+
+    #[test]
+    fn unknown_trait() {
+        let text = format!("{}traits = [\"GeometricProduct\", 'Exponential']\n", GENERATORS);
+        let error = config_error(&text);
+        assert_eq!((error.offset, error.token.as_str()), (text.find("Exponential").unwrap(), "Exponential"));
+        assert_eq!(error.message, "`Exponential` is not the name of a generated trait");
+        assert_eq!(
+            error.to_string(),
+            "error: `Exponential` is not the name of a generated trait\n  \
+             --> algebra.toml:8:32\n   \
+             | traits = [\"GeometricProduct\", 'Exponential']\n   \
+             |                                ^^^^^^^^^^^"
+        );
+    }
+
+    #[test]
+    fn escaped_strings_are_underlined_completely() {
+        let text = format!("{}traits = [\"\"\"Exponential\"\"\"]\n", GENERATORS);
+        let error = config_error(&text);
+        assert_eq!(
+            (error.offset, error.token.as_str()),
+            (text.find("\"\"\"").unwrap(), "\"\"\"Exponential\"\"\"")
+        );
+        let text = format!("{}[[classes]]\nname = \"Li\\u006Ee!\"\ngroups = [[\"e1\"]]\n", GENERATORS);
+        let error = config_error(&text);
+        assert_eq!((error.offset, error.token.as_str()), (text.find("\"Li").unwrap(), "\"Li\\u006Ee!\""));
+        assert_eq!(error.message, "expected a class name");
+    }
+
+    #[test]
+    fn wedge_element_names() {
+        let text = format!(
+            "{}[[classes]]\nname = \"Line\"\ngroups = [[\"e1^e0\", \"-e0^e1^e3\", \"e3^e2\"], [\"-e0^e2\", \"-e2^e1\"]]\n",
+            GENERATORS
+        );
+        let descriptor = parse_config("algebra.toml", &text).unwrap();
+        let elements = descriptor.classes[0]
+            .flat_basis()
+            .iter()
+            .map(|element| (element.scalar, element.index))
+            .collect::<Vec<_>>();
+        assert_eq!(elements, [(-1, 0b0011), (-1, 0b1011), (-1, 0b1100), (-1, 0b0101), (1, 0b0110)]);
+    }
+
+    #[test]
+    fn wedge_element_errors() {
+        let text = format!("{}[[classes]]\nname = \"Line\"\ngroups = [[\"e1^e2^e1\"]]\n", GENERATORS);
+        let error = config_error(&text);
+        assert_eq!((error.offset, error.token.as_str()), (text.find("e1^e2^e1").unwrap(), "e1^e2^e1"));
+        assert_eq!(error.message, "generator `e1` occurs more than once");
+        let text = format!("{}[[classes]]\nname = \"Line\"\ngroups = [[\"-e1^e4\"]]\n", GENERATORS);
+        let error = config_error(&text);
+        assert_eq!((error.offset, error.token.as_str()), (text.find("-e1^e4").unwrap(), "-e1^e4"));
+        assert_eq!(error.message, "`e4` is not the name of a generator");
+    }
+
+    #[test]
+    fn toml_syntax_error() {
+        let text = GENERATORS.replace("square = 1 },\n    { name = \"e3\"", "square = 1 }\n    { name = \"e3\"");
+        let error = config_error(&text);
+        assert_eq!((error.offset, error.token.as_str()), (text.find("{ name = \"e3\"").unwrap(), "{"));
+        assert_eq!(
+            error.to_string(),
+            "error: invalid array\nexpected `]`\n  \
+             --> algebra.toml:6:5\n   \
+             |     { name = \"e3\", square = 1 },\n   \
+             |     ^"
+        );
+    }
+
+    /// Generates the prebuilt algebra `algebras/<algebra_name>.toml` with only the given trait selected
+    fn generate_with_trait(algebra_name: &str, trait_name: &str) -> String {
+        let text = std::fs::read_to_string(format!("{}/algebras/{}.toml", env!("CARGO_MANIFEST_DIR"), algebra_name)).unwrap();
+        let text = text.replacen("[[classes]]", &format!("traits = [\"{}\"]\n\n[[classes]]", trait_name), 1);
+        let out_dir = std::env::temp_dir().join(format!("geometric_algebra_{}_{}_{}", std::process::id(), algebra_name, trait_name));
+        let path = out_dir.join("config.toml");
+        std::fs::create_dir_all(&out_dir).unwrap();
+        std::fs::write(&path, text).unwrap();
+        let code = std::fs::read_to_string(crate::generate_from_config(&path, &out_dir).unwrap()).unwrap();
+        std::fs::remove_dir_all(&out_dir).unwrap();
+        code
+    }
+
+    #[test]
+    fn selected_traits_include_their_dependencies() {
+        let methods = TRAIT_NAMES
+            .iter()
+            .map(|name| {
+                let mut method = Vec::new();
+                crate::emit::camel_to_snake_case(&mut method, name).unwrap();
+                (*name, String::from_utf8(method).unwrap())
+            })
+            .collect::<Vec<_>>();
+        for algebra_name in ["ppga3d", "cga3d"] {
+            for trait_name in TRAIT_NAMES.iter() {
+                let code = generate_with_trait(algebra_name, trait_name);
+                for (called, method) in methods.iter() {
+                    let calls = match *called {
+                        // Scalars have a `sqrt` method as well
+                        "Sqrt" => false,
+                        "Zero" | "One" => code.contains(&format!("::{}()", method)),
+                        _ => code.contains(&format!(".{}(", method)),
+                    };
+                    let emitted = code.contains(&format!("impl {} for ", called)) || code.contains(&format!("impl {}<", called));
+                    assert!(
+                        !calls || emitted,
+                        "selecting `{}` in {} calls `{}` which is not emitted",
+                        trait_name,
+                        algebra_name,
+                        called
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+/// CUDA code generation from AST
+///
+/// CUDA is a dialect of C++, so this reuses the C backend and only marks all functions as callable from host and device code.
+/// Thus the classes have the same memory layout as in C and Rust, and can be copied between host and device without conversion.
+use crate::{
+    ast::{AstNode, ScalarType},
+    c,
+};
+
+/// Main function to emit CUDA code for an AST node
+pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, scalar_type: ScalarType, indentation: usize) -> std::io::Result<()> {
+    c::emit_code_with_qualifiers(collector, ast_node, scalar_type, indentation, "__host__ __device__ inline")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{assert_balanced_brackets, block_lines, emit::Target, emit_preset};
+
+    #[test]
+    fn ppga3d_structure() {
+        let code = emit_preset("ppga3d", Target::Cuda).unwrap();
+        assert_balanced_brackets(&code);
+
+        // One union of aligned groups and elements per class, like the C header
+        assert!(!code.contains("union Scalar"));
+        for (class, groups, elements) in [
+            ("MultiVector", 4, 16),
+            ("Rotor", 1, 4),
+            ("Point", 1, 4),
+            ("IdealPoint", 1, 4),
+            ("Plane", 1, 4),
+            ("Line", 2, 8),
+            ("Translator", 1, 4),
+            ("Motor", 2, 8),
+            ("PointAndPlane", 2, 8),
+        ] {
+            let mut members = vec!["struct {".to_string()];
+            members.extend((0..groups).map(|i| format!("GEOMETRIC_ALGEBRA_ALIGN(GEOMETRIC_ALGEBRA_F32_ALIGNMENT) float g{}[4];", i)));
+            members.push("} groups;".to_string());
+            members.push(format!("float elements[{}];", elements));
+            assert_eq!(
+                block_lines(&code, &format!("typedef union {} {{", class), &format!("}} {};", class)),
+                members,
+                "{}",
+                class
+            );
+        }
+
+        // All functions are callable from host and device code and named after the classes in snake case and the trait
+        for line in code.lines().filter(|line| line.ends_with(") {") && !line.starts_with(' ')) {
+            assert!(line.starts_with("__host__ __device__ inline "), "`{}` is not qualified", line);
+        }
+        for signature in [
+            "__host__ __device__ inline Line line_new(float e01, float e02, float e03, float e23, float _e13, float e12) {",
+            "__host__ __device__ inline float scalar_one(void) {",
+            "__host__ __device__ inline Motor motor_motor_geometric_product(Motor self, Motor other) {",
+            "__host__ __device__ inline Point motor_point_transformation(Motor self, Point other) {",
+            "__host__ __device__ inline float line_ideal_norm(Line self) {",
+        ] {
+            assert!(code.lines().any(|line| line == signature), "`{}` is missing", signature);
+        }
+    }
+}
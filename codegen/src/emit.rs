@@ -2,7 +2,11 @@
 /// This code bridges the abstract syntax tree (AST) representation
 /// with concrete code generation, allowing the same DSL to target
 /// both CPU-side Rust code and GPU-side GLSL shader code
-use crate::{algebra::BasisElement, ast::AstNode, glsl, rust};
+use crate::{
+    algebra::BasisElement,
+    ast::{AstNode, ScalarType},
+    c, cuda, glsl, hlsl, msl, python, rust, rust_batch, wgsl,
+};
 
 /// Converts camelCase names to snake_case (with handling of consecutive uppercase letters)
 pub fn camel_to_snake_case<W: std::io::Write>(collector: &mut W, name: &str) -> std::io::Result<()> {
@@ -55,28 +59,121 @@ pub fn emit_element_name<W: std::io::Write>(collector: &mut W, element: &BasisEl
     }
 }
 
-/// Main code emitter (handles both Rust and GLSL output)
+/// Language to emit code in
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Target {
+    Rust,
+    Glsl,
+    Wgsl,
+    Hlsl,
+    Msl,
+    C,
+    Cuda,
+    Python,
+}
+
+impl Target {
+    /// All targets, in the order they are emitted in
+    pub const ALL: &'static [Self] = &[
+        Self::Rust,
+        Self::Glsl,
+        Self::Wgsl,
+        Self::Hlsl,
+        Self::Msl,
+        Self::C,
+        Self::Cuda,
+        Self::Python,
+    ];
+
+    /// Parses the name of the target as used on the command-line (e.g. "rust")
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|target| target.name() == name)
+    }
+
+    /// Name of the target as used on the command-line
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Rust => "rust",
+            Self::Glsl => "glsl",
+            Self::Wgsl => "wgsl",
+            Self::Hlsl => "hlsl",
+            Self::Msl => "msl",
+            Self::C => "c",
+            Self::Cuda => "cuda",
+            Self::Python => "python",
+        }
+    }
+
+    /// File extension of the emitted source code
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Rust => "rs",
+            Self::Glsl => "glsl",
+            Self::Wgsl => "wgsl",
+            Self::Hlsl => "hlsl",
+            Self::Msl => "metal",
+            Self::C => "h",
+            Self::Cuda => "cuh",
+            Self::Python => "py",
+        }
+    }
+
+    /// Checks if the language has the given scalar type (WGSL and MSL have no double precision)
+    pub fn supports(&self, scalar_type: ScalarType) -> bool {
+        !matches!((self, scalar_type), (Self::Wgsl | Self::Msl, ScalarType::F64))
+    }
+
+    /// Checks if the language has the elementary functions which the trait of the given name calls (`Exp`, `Ln`, `Powf`, `Sqrt` and `Normalize` are only emitted for Rust and single precision GLSL)
+    pub fn supports_trait(&self, name: &str, scalar_type: ScalarType) -> bool {
+        !matches!(name, "Exp" | "Ln" | "Powf" | "Sqrt" | "Normalize")
+            || matches!((self, scalar_type), (Self::Rust, _) | (Self::Glsl, ScalarType::F32))
+    }
+
+    /// Checks if the language can hold groups of the given number of basis elements (shading languages have no vectors of more than four components)
+    pub fn supports_group_size(&self, group_size: usize) -> bool {
+        group_size <= 4 || matches!(self, Self::Rust | Self::C | Self::Cuda | Self::Python)
+    }
+}
+
+/// Main code emitter (handles all selected targets at once)
 pub struct Emitter<W: std::io::Write> {
-    pub rust_collector: W,
-    pub glsl_collector: W,
+    pub collectors: Vec<(Target, W)>,
+    pub scalar_type: ScalarType,
 }
 
-impl Emitter<std::fs::File> {
-    /// Output code to .rs and .glsl files
-    pub fn new(path: &std::path::Path) -> Self {
+impl Emitter<Vec<u8>> {
+    /// Collects the code of each target in memory, so that nothing is written if emission fails halfway
+    pub fn new(targets: &[Target], scalar_type: ScalarType) -> Self {
         Self {
-            // Create output files with appropriate extensions
-            rust_collector: std::fs::File::create(path.with_extension("rs")).unwrap(),
-            glsl_collector: std::fs::File::create(path.with_extension("glsl")).unwrap(),
+            collectors: targets.iter().map(|target| (*target, Vec::new())).collect(),
+            scalar_type,
         }
     }
 }
 
 impl<W: std::io::Write> Emitter<W> {
-    /// Emits code for both Rust and GLSL from an AST node
+    /// Emits code for all targets from an AST node
     pub fn emit(&mut self, ast_node: &AstNode) -> std::io::Result<()> {
-        rust::emit_code(&mut self.rust_collector, ast_node, 0)?;
-        glsl::emit_code(&mut self.glsl_collector, ast_node, 0)?;
+        for (target, collector) in self.collectors.iter_mut() {
+            if let AstNode::TraitImplementation { result, .. } = ast_node {
+                if !target.supports_trait(result.name, self.scalar_type) {
+                    continue;
+                }
+            }
+            match target {
+                Target::Rust => {
+                    rust::emit_code(collector, ast_node, self.scalar_type, 0)?;
+                    rust_batch::emit_code(collector, ast_node, self.scalar_type, 0)?;
+                }
+                Target::Glsl => glsl::emit_code(collector, ast_node, self.scalar_type, 0)?,
+                Target::Wgsl => wgsl::emit_code(collector, ast_node, self.scalar_type, 0)?,
+                Target::Hlsl => hlsl::emit_code(collector, ast_node, self.scalar_type, 0)?,
+                Target::Msl => msl::emit_code(collector, ast_node, self.scalar_type, 0)?,
+                Target::C => c::emit_code(collector, ast_node, self.scalar_type, 0)?,
+                Target::Cuda => cuda::emit_code(collector, ast_node, self.scalar_type, 0)?,
+                Target::Python => python::emit_code(collector, ast_node, self.scalar_type, 0)?,
+            }
+        }
         Ok(())
     }
 }
@@ -1,25 +1,168 @@
 /// GLSL code generation from AST
 use crate::{
-    ast::{AstNode, DataType, Expression, ExpressionContent},
+    ast::{AstNode, DataType, Expression, ExpressionContent, ScalarType},
     emit::{camel_to_snake_case, emit_indentation},
 };
 
 /// Component names for GLSL vector swizzling
 const COMPONENT: &[&str] = &["x", "y", "z", "w"];
 
+/// Helper functions of the generated `exp`, `ln` and `sqrt`, a port of `BivectorSplit` in the Rust crate
+///
+/// Complex numbers are `vec2` and the power series are evaluated close to their singularities.
+const BIVECTOR_SPLIT: &str = r#"const float SINHC_SQRT_SERIES[8] = float[](1.0, 1.0 / 6.0, 1.0 / 120.0, 1.0 / 5040.0, 1.0 / 362880.0, 1.0 / 39916800.0, 1.0 / 6227020800.0, 1.0 / 1307674368000.0);
+const float ACOSHC_SERIES[13] = float[](1.0, -1.0 / 3.0, 2.0 / 15.0, -2.0 / 35.0, 8.0 / 315.0, -8.0 / 693.0, 16.0 / 3003.0, -16.0 / 6435.0, 128.0 / 109395.0, -128.0 / 230945.0, 256.0 / 969969.0, -256.0 / 2028117.0, 1024.0 / 16900975.0);
+
+vec2 bivector_split_mul(vec2 a, vec2 b) {
+    return vec2(a.x * b.x - a.y * b.y, a.x * b.y + a.y * b.x);
+}
+
+vec2 bivector_split_div(vec2 a, vec2 b) {
+    return vec2(a.x * b.x + a.y * b.y, a.y * b.x - a.x * b.y) / (b.x * b.x + b.y * b.y);
+}
+
+vec2 bivector_split_sqrt(vec2 z) {
+    float magnitude = length(z);
+    float imaginary = sqrt(0.5 * (magnitude - z.x));
+    return vec2(sqrt(0.5 * (magnitude + z.x)), z.y < 0.0 ? -imaginary : imaginary);
+}
+
+vec2 bivector_split_ln(vec2 z) {
+    return vec2(0.5 * log(z.x * z.x + z.y * z.y), atan(z.y, z.x));
+}
+
+vec2 bivector_split_cosh_sqrt(vec2 z) {
+    vec2 root = bivector_split_sqrt(z);
+    return vec2(cosh(root.x) * cos(root.y), sinh(root.x) * sin(root.y));
+}
+
+vec2 bivector_split_sinhc_sqrt(vec2 z) {
+    vec2 root = bivector_split_sqrt(z);
+    if (root.x == 0.0 && root.y == 0.0) {
+        return vec2(1.0, 0.0);
+    }
+    return bivector_split_div(vec2(sinh(root.x) * cos(root.y), cosh(root.x) * sin(root.y)), root);
+}
+
+vec2 bivector_split_inverse_sqrt(vec2 z) {
+    return bivector_split_div(vec2(1.0, 0.0), bivector_split_sqrt(z));
+}
+
+float bivector_split_sinhc_sqrt_derivative(float x) {
+    if (x * x < 0.01) {
+        float derivative = 0.0;
+        for (int k = 7; k > 0; --k) {
+            derivative = derivative * x + float(k) * SINHC_SQRT_SERIES[k];
+        }
+        return derivative;
+    }
+    return (bivector_split_cosh_sqrt(vec2(x, 0.0)).x - bivector_split_sinhc_sqrt(vec2(x, 0.0)).x) / (2.0 * x);
+}
+
+vec2 bivector_split_acoshc(vec2 z) {
+    vec2 u = vec2(z.x - 1.0, z.y);
+    if (u.x * u.x + u.y * u.y < 0.01) {
+        vec2 value = vec2(0.0);
+        for (int k = 12; k >= 0; --k) {
+            value = bivector_split_mul(value, u) + vec2(ACOSHC_SERIES[k], 0.0);
+        }
+        return value;
+    }
+    vec2 root = bivector_split_sqrt(bivector_split_mul(z, z) - vec2(1.0, 0.0));
+    return bivector_split_div(bivector_split_ln(z + root), root);
+}
+
+float bivector_split_acoshc_derivative(float x) {
+    float u = x - 1.0;
+    if (u * u < 0.01) {
+        float derivative = 0.0;
+        for (int k = 12; k > 0; --k) {
+            derivative = derivative * u + float(k) * ACOSHC_SERIES[k];
+        }
+        return derivative;
+    }
+    return (x * bivector_split_acoshc(vec2(x, 0.0)).x - 1.0) / (1.0 - x * x);
+}
+
+// f(a + P) evaluates to f0 + f1 * P, given f(a + sqrt(square)), f(a - sqrt(square)) and f'(a)
+// where f(a + sqrt(square)) is complex for a negative square
+vec2 bivector_split(float square, vec2 plus, float minus, float derivative) {
+    if (square > 0.0) {
+        return vec2(0.5 * (plus.x + minus), 0.5 * (plus.x - minus) / sqrt(square));
+    } else if (square < 0.0) {
+        return vec2(plus.x, plus.y / sqrt(-square));
+    }
+    return vec2(plus.x, derivative);
+}
+
+vec2 bivector_split_plus(float a, float square) {
+    return square < 0.0 ? vec2(a, sqrt(-square)) : vec2(a + sqrt(square), 0.0);
+}
+
+vec4 exp_coefficients(float inner, float wedge_square) {
+    vec2 plus = bivector_split_plus(inner, wedge_square);
+    vec2 minus = vec2(inner - sqrt(max(wedge_square, 0.0)), 0.0);
+    vec2 c = bivector_split(wedge_square, bivector_split_cosh_sqrt(plus), bivector_split_cosh_sqrt(minus).x, 0.5 * bivector_split_sinhc_sqrt(vec2(inner, 0.0)).x);
+    vec2 s = bivector_split(wedge_square, bivector_split_sinhc_sqrt(plus), bivector_split_sinhc_sqrt(minus).x, bivector_split_sinhc_sqrt_derivative(inner));
+    return vec4(c, s);
+}
+
+vec4 ln_coefficients(float magnitude, float scalar, float quadvector_square) {
+    float a = scalar / magnitude;
+    float square = quadvector_square / (magnitude * magnitude);
+    vec2 minus = vec2(a - sqrt(max(square, 0.0)), 0.0);
+    vec2 l = bivector_split(square, bivector_split_acoshc(bivector_split_plus(a, square)), bivector_split_acoshc(minus).x, bivector_split_acoshc_derivative(a));
+    return vec4(log(magnitude), l.x / magnitude, l.y / (magnitude * magnitude), 0.0);
+}
+
+vec4 inverse_sqrt_coefficients(float scalar, float quadvector_square) {
+    vec2 minus = vec2(scalar - sqrt(max(quadvector_square, 0.0)), 0.0);
+    vec2 f = bivector_split(quadvector_square, bivector_split_inverse_sqrt(bivector_split_plus(scalar, quadvector_square)), bivector_split_inverse_sqrt(minus).x, -0.5 / (scalar * sqrt(scalar)));
+    return vec4(f, 0.0, 0.0);
+}
+"#;
+
+/// Emits the GLSL representation of a scalar type
+fn emit_scalar_type<W: std::io::Write>(collector: &mut W, scalar_type: ScalarType) -> std::io::Result<()> {
+    match scalar_type {
+        ScalarType::F32 => collector.write_all(b"float"),
+        ScalarType::F64 => collector.write_all(b"double"),
+    }
+}
+
 /// Emits the GLSL representation of a data type
-fn emit_data_type<W: std::io::Write>(collector: &mut W, data_type: &DataType) -> std::io::Result<()> {
+fn emit_data_type<W: std::io::Write>(collector: &mut W, data_type: &DataType, scalar_type: ScalarType) -> std::io::Result<()> {
     match data_type {
         DataType::Integer => collector.write_all(b"int"),
-        DataType::SimdVector(size) if *size == 1 => collector.write_all(b"float"), // Size-1 vectors are floats
-        DataType::SimdVector(size) => collector.write_fmt(format_args!("vec{}", *size)), // vecN notation
-        DataType::MultiVector(class) if class.is_scalar() => collector.write_all(b"float"), // Scalar multivectors
-        DataType::MultiVector(class) => collector.write_all(class.class_name.as_bytes()), // Other multivectors
+        DataType::SimdVector(size) if *size == 1 => emit_scalar_type(collector, scalar_type), // Size-1 vectors are scalars
+        DataType::SimdVector(size) => match scalar_type {
+            ScalarType::F32 => collector.write_fmt(format_args!("vec{}", *size)), // vecN notation
+            ScalarType::F64 => collector.write_fmt(format_args!("dvec{}", *size)), // dvecN notation
+        },
+        DataType::MultiVector(class) if class.is_scalar() => emit_scalar_type(collector, scalar_type), // Scalar multivectors
+        DataType::MultiVector(class) => collector.write_all(class.class_name.as_bytes()),              // Other multivectors
+    }
+}
+
+/// Emits scalar struct members which pad the groups to the memory layout of the Rust classes
+fn emit_padding_members<W: std::io::Write>(
+    collector: &mut W,
+    padding_index: &mut usize,
+    count: usize,
+    scalar_type: ScalarType,
+    indentation: usize,
+) -> std::io::Result<()> {
+    for _ in 0..count {
+        emit_indentation(collector, indentation)?;
+        emit_data_type(collector, &DataType::SimdVector(1), scalar_type)?;
+        collector.write_fmt(format_args!(" padding{};\n", padding_index))?;
+        *padding_index += 1;
     }
+    Ok(())
 }
 
 /// Recursively emits GLSL code for an expression
-fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression) -> std::io::Result<()> {
+fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression, scalar_type: ScalarType) -> std::io::Result<()> {
     match &expression.content {
         ExpressionContent::None => unreachable!(),
 
@@ -30,7 +173,36 @@ fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression
 
         // Special case for scalar constructor calls
         ExpressionContent::InvokeClassMethod(class, "Constructor", arguments) if class.is_scalar() => {
-            emit_expression(collector, &arguments[0].1)?;
+            emit_expression(collector, &arguments[0].1, scalar_type)?;
+        }
+
+        // Constructor from the individual elements, which are packed into the vectors of the groups
+        ExpressionContent::InvokeClassMethod(class, "New", arguments) => {
+            collector.write_fmt(format_args!("{}(", &class.class_name))?;
+            let (group_padding, trailing_padding) = class.padding();
+            let mut arguments = arguments.iter();
+            for (i, (group, (before, after))) in class.grouped_basis.iter().zip(group_padding.iter()).enumerate() {
+                if i > 0 {
+                    collector.write_all(b", ")?;
+                }
+                collector.write_all(b"0.0, ".repeat(*before).as_slice())?;
+                if group.len() > 1 {
+                    emit_data_type(collector, &DataType::SimdVector(group.len()), scalar_type)?;
+                    collector.write_all(b"(")?;
+                }
+                for (j, (_argument_class, argument)) in arguments.by_ref().take(group.len()).enumerate() {
+                    if j > 0 {
+                        collector.write_all(b", ")?;
+                    }
+                    emit_expression(collector, argument, scalar_type)?;
+                }
+                if group.len() > 1 {
+                    collector.write_all(b")")?;
+                }
+                collector.write_all(b", 0.0".repeat(*after).as_slice())?;
+            }
+            collector.write_all(b", 0.0".repeat(trailing_padding).as_slice())?;
+            collector.write_all(b")")?;
         }
 
         // Method invocations (both class and instance methods)
@@ -54,7 +226,7 @@ fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression
 
                     // Start function call and emit instance expression
                     collector.write_all(b"(")?;
-                    emit_expression(collector, inner_expression)?;
+                    emit_expression(collector, inner_expression, scalar_type)?;
                     if !arguments.is_empty() {
                         collector.write_all(b", ")?;
                     }
@@ -77,11 +249,25 @@ fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression
             }
 
             // Emit all arguments, comma-separated
-            for (i, (_argument_class, argument)) in arguments.iter().enumerate() {
-                if i > 0 {
-                    collector.write_all(b", ")?;
+            if let ExpressionContent::InvokeClassMethod(class, "Constructor", _) = &expression.content {
+                // Constructors also need values for the padding members
+                let (group_padding, trailing_padding) = class.padding();
+                for (i, ((_argument_class, argument), (before, after))) in arguments.iter().zip(group_padding.iter()).enumerate() {
+                    if i > 0 {
+                        collector.write_all(b", ")?;
+                    }
+                    collector.write_all(b"0.0, ".repeat(*before).as_slice())?;
+                    emit_expression(collector, argument, scalar_type)?;
+                    collector.write_all(b", 0.0".repeat(*after).as_slice())?;
+                }
+                collector.write_all(b", 0.0".repeat(trailing_padding).as_slice())?;
+            } else {
+                for (i, (_argument_class, argument)) in arguments.iter().enumerate() {
+                    if i > 0 {
+                        collector.write_all(b", ")?;
+                    }
+                    emit_expression(collector, argument, scalar_type)?;
                 }
-                emit_expression(collector, argument)?;
             }
             collector.write_all(b")")?;
         }
@@ -93,23 +279,23 @@ fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression
             collector.write_all(b"_")?;
             camel_to_snake_case(collector, &destination_class.class_name)?;
             collector.write_all(b"_into(")?;
-            emit_expression(collector, inner_expression)?;
+            emit_expression(collector, inner_expression, scalar_type)?;
             collector.write_all(b")")?;
         }
 
         // Ternary conditional operator
         ExpressionContent::Select(condition_expression, then_expression, else_expression) => {
             collector.write_all(b"(")?;
-            emit_expression(collector, condition_expression)?;
+            emit_expression(collector, condition_expression, scalar_type)?;
             collector.write_all(b") ? ")?;
-            emit_expression(collector, then_expression)?;
+            emit_expression(collector, then_expression, scalar_type)?;
             collector.write_all(b" : ")?;
-            emit_expression(collector, else_expression)?;
+            emit_expression(collector, else_expression, scalar_type)?;
         }
 
         // Array/vector element access using GLSL's .gN syntax
         ExpressionContent::Access(inner_expression, array_index) => {
-            emit_expression(collector, inner_expression)?;
+            emit_expression(collector, inner_expression, scalar_type)?;
             if !inner_expression.is_scalar() {
                 collector.write_fmt(format_args!(".g{}", array_index))?;
             }
@@ -117,7 +303,7 @@ fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression
 
         // GLSL vector swizzling (.xyzw)
         ExpressionContent::Swizzle(inner_expression, indices) => {
-            emit_expression(collector, inner_expression)?;
+            emit_expression(collector, inner_expression, scalar_type)?;
             collector.write_all(b".")?;
             for component_index in indices.iter() {
                 collector.write_all(COMPONENT[*component_index].bytes().collect::<Vec<_>>().as_slice())?;
@@ -128,11 +314,11 @@ fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression
         ExpressionContent::Gather(inner_expression, indices) => {
             if expression.size == 1 && inner_expression.is_scalar() {
                 // Simple case - just emit the inner expression
-                emit_expression(collector, inner_expression)?;
+                emit_expression(collector, inner_expression, scalar_type)?;
             } else {
                 // Vector construction from components
                 if expression.size > 1 {
-                    emit_data_type(collector, &DataType::SimdVector(expression.size))?;
+                    emit_data_type(collector, &DataType::SimdVector(expression.size), scalar_type)?;
                     collector.write_all(b"(")?;
                 }
 
@@ -141,7 +327,7 @@ fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression
                     if i > 0 {
                         collector.write_all(b", ")?;
                     }
-                    emit_expression(collector, inner_expression)?;
+                    emit_expression(collector, inner_expression, scalar_type)?;
                     if !inner_expression.is_scalar() {
                         // Access array element
                         collector.write_fmt(format_args!(".g{}", array_index))?;
@@ -167,7 +353,7 @@ fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression
                     collector.write_fmt(format_args!("{:.1}", values[0] as f32))?
                 } else {
                     // Vector constructor with components
-                    emit_data_type(collector, &DataType::SimdVector(expression.size))?;
+                    emit_data_type(collector, &DataType::SimdVector(expression.size), scalar_type)?;
                     collector.write_fmt(format_args!(
                         "({})",
                         values.iter().map(|value| format!("{:.1}", *value as f32)).collect::<Vec<_>>().join(", ")
@@ -180,11 +366,20 @@ fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression
         // Mathematical function
         ExpressionContent::SquareRoot(inner_expression) => {
             collector.write_all(b"sqrt(")?;
-            emit_expression(collector, inner_expression)?;
+            emit_expression(collector, inner_expression, scalar_type)?;
             collector.write_all(b")")?;
         }
 
         // Binary operations
+        // Fused multiply-add, which the shader compiler contracts on its own
+        ExpressionContent::MultiplyAdd(a, b, c) => {
+            emit_expression(collector, c, scalar_type)?;
+            collector.write_all(b" + ")?;
+            emit_expression(collector, a, scalar_type)?;
+            collector.write_all(b" * ")?;
+            emit_expression(collector, b, scalar_type)?;
+        }
+
         ExpressionContent::Add(lhs, rhs)
         | ExpressionContent::Subtract(lhs, rhs)
         | ExpressionContent::Multiply(lhs, rhs)
@@ -199,7 +394,7 @@ fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression
             }
 
             // Left operand
-            emit_expression(collector, lhs)?;
+            emit_expression(collector, lhs, scalar_type)?;
 
             // Operator
             collector.write_all(match expression.content {
@@ -215,7 +410,7 @@ fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression
             })?;
 
             // Right operand
-            emit_expression(collector, rhs)?;
+            emit_expression(collector, rhs, scalar_type)?;
             if let ExpressionContent::LogicAnd(_, _) = expression.content {
                 collector.write_all(b")")?;
             }
@@ -225,10 +420,15 @@ fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression
 }
 
 /// Main function to emit GLSL code for an AST node
-pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, indentation: usize) -> std::io::Result<()> {
+pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, scalar_type: ScalarType, indentation: usize) -> std::io::Result<()> {
     match ast_node {
         AstNode::None => {}
-        AstNode::Preamble => {}
+        AstNode::Preamble { .. } => {
+            // The closed-form exponential and logarithm need the coefficients of the bivector split
+            if scalar_type == ScalarType::F32 {
+                collector.write_all(BIVECTOR_SPLIT.as_bytes())?;
+            }
+        }
 
         // Struct definition for multivector class
         AstNode::ClassDefinition { class } => {
@@ -237,8 +437,17 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
             }
 
             // Generate struct with fields for basis element groups
+            if let Some(doc) = &class.doc {
+                for line in doc.lines() {
+                    collector.write_fmt(format_args!("//{}{}\n", if line.is_empty() { "" } else { " " }, line))?;
+                }
+            }
             collector.write_fmt(format_args!("struct {} {{\n", class.class_name))?;
-            for (i, group) in class.grouped_basis.iter().enumerate() {
+            let (group_padding, trailing_padding) = class.padding();
+            let mut padding_index = 0;
+            for (i, (group, (before, after))) in class.grouped_basis.iter().zip(group_padding.iter()).enumerate() {
+                emit_padding_members(collector, &mut padding_index, *before, scalar_type, indentation + 1)?;
+
                 // Comment showing the basis elements in this group
                 emit_indentation(collector, indentation + 1)?;
                 collector.write_all(b"// ")?;
@@ -252,9 +461,11 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
 
                 // Field declaration using appropriate vector type
                 emit_indentation(collector, indentation + 1)?;
-                emit_data_type(collector, &DataType::SimdVector(group.len()))?;
+                emit_data_type(collector, &DataType::SimdVector(group.len()), scalar_type)?;
                 collector.write_fmt(format_args!(" g{};\n", i))?;
+                emit_padding_members(collector, &mut padding_index, *after, scalar_type, indentation + 1)?;
             }
+            emit_padding_members(collector, &mut padding_index, trailing_padding, scalar_type, indentation + 1)?;
             emit_indentation(collector, indentation)?;
             collector.write_all(b"};\n\n")?;
         }
@@ -262,7 +473,7 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
         // Return statement
         AstNode::ReturnStatement { expression } => {
             collector.write_all(b"return ")?;
-            emit_expression(collector, expression)?;
+            emit_expression(collector, expression, scalar_type)?;
             collector.write_all(b";\n")?;
         }
 
@@ -270,11 +481,11 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
         AstNode::VariableAssignment { name, data_type, expression } => {
             if let Some(data_type) = data_type {
                 // Include type for declarations
-                emit_data_type(collector, data_type)?;
+                emit_data_type(collector, data_type, scalar_type)?;
                 collector.write_all(b" ")?;
             }
             collector.write_fmt(format_args!("{} = ", name))?;
-            emit_expression(collector, expression)?;
+            emit_expression(collector, expression, scalar_type)?;
             collector.write_all(b";\n")?;
         }
 
@@ -288,13 +499,13 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
 
             // Condition and block opening
             collector.write_all(b"(")?;
-            emit_expression(collector, condition)?;
+            emit_expression(collector, condition, scalar_type)?;
             collector.write_all(b") {\n")?;
 
             // Body statements
             for statement in body.iter() {
                 emit_indentation(collector, indentation + 1)?;
-                emit_code(collector, statement, indentation + 1)?;
+                emit_code(collector, statement, scalar_type, indentation + 1)?;
             }
 
             // Block closing
@@ -305,7 +516,7 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
         // Function definition
         AstNode::TraitImplementation { result, parameters, body } => {
             // Return type and function name construction
-            emit_data_type(collector, &result.data_type)?;
+            emit_data_type(collector, &result.data_type, scalar_type)?;
             collector.write_all(b" ")?;
 
             // Generate function name based on parameter count and types
@@ -339,7 +550,7 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
                 if i > 0 {
                     collector.write_all(b", ")?;
                 }
-                emit_data_type(collector, &parameter.data_type)?;
+                emit_data_type(collector, &parameter.data_type, scalar_type)?;
                 collector.write_fmt(format_args!(" {}", parameter.name))?;
             }
             collector.write_all(b") {\n")?;
@@ -347,7 +558,7 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
             // Function body
             for statement in body.iter() {
                 emit_indentation(collector, indentation + 1)?;
-                emit_code(collector, statement, indentation + 1)?;
+                emit_code(collector, statement, scalar_type, indentation + 1)?;
             }
 
             // Function closing
@@ -0,0 +1,506 @@
+/// HLSL code generation from AST
+use crate::{
+    ast::{AstNode, DataType, Expression, ExpressionContent, ScalarType},
+    emit::{camel_to_snake_case, emit_indentation},
+};
+
+/// Component names for HLSL vector swizzling
+const COMPONENT: &[&str] = &["x", "y", "z", "w"];
+
+/// Emits the HLSL representation of a scalar type
+fn emit_scalar_type<W: std::io::Write>(collector: &mut W, scalar_type: ScalarType) -> std::io::Result<()> {
+    match scalar_type {
+        ScalarType::F32 => collector.write_all(b"float"),
+        ScalarType::F64 => collector.write_all(b"double"),
+    }
+}
+
+/// Emits the HLSL representation of a data type
+fn emit_data_type<W: std::io::Write>(collector: &mut W, data_type: &DataType, scalar_type: ScalarType) -> std::io::Result<()> {
+    match data_type {
+        DataType::Integer => collector.write_all(b"int"),
+        DataType::SimdVector(size) if *size == 1 => emit_scalar_type(collector, scalar_type), // Size-1 vectors are scalars
+        DataType::SimdVector(size) => {
+            // floatN / doubleN notation
+            emit_scalar_type(collector, scalar_type)?;
+            collector.write_fmt(format_args!("{}", *size))
+        }
+        DataType::MultiVector(class) if class.is_scalar() => emit_scalar_type(collector, scalar_type), // Scalar multivectors
+        DataType::MultiVector(class) => collector.write_all(class.class_name.as_bytes()),              // Other multivectors
+    }
+}
+
+/// Recursively emits HLSL code for an expression
+fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression, scalar_type: ScalarType) -> std::io::Result<()> {
+    match &expression.content {
+        ExpressionContent::None => unreachable!(),
+
+        // Variable reference
+        ExpressionContent::Variable(_data_type, name) => {
+            collector.write_all(name.bytes().collect::<Vec<_>>().as_slice())?;
+        }
+
+        // Special case for scalar constructor calls
+        ExpressionContent::InvokeClassMethod(class, "Constructor", arguments) if class.is_scalar() => {
+            emit_expression(collector, &arguments[0].1, scalar_type)?;
+        }
+
+        // Method invocations (both class and instance methods)
+        ExpressionContent::InvokeClassMethod(_, _, arguments) | ExpressionContent::InvokeInstanceMethod(_, _, _, _, arguments) => {
+            match &expression.content {
+                // Instance method call
+                ExpressionContent::InvokeInstanceMethod(result_class, inner_expression, method_name, _, _) => {
+                    // Build function name from result class, argument classes, and method name
+                    if let DataType::MultiVector(result_class) = result_class {
+                        camel_to_snake_case(collector, &result_class.class_name)?;
+                        collector.write_all(b"_")?;
+                    }
+                    // Include argument class names in function name
+                    for (argument_class, _argument) in arguments.iter() {
+                        if let DataType::MultiVector(argument_class) = argument_class {
+                            camel_to_snake_case(collector, &argument_class.class_name)?;
+                            collector.write_all(b"_")?;
+                        }
+                    }
+                    camel_to_snake_case(collector, method_name)?;
+
+                    // Start function call and emit instance expression
+                    collector.write_all(b"(")?;
+                    emit_expression(collector, inner_expression, scalar_type)?;
+                    if !arguments.is_empty() {
+                        collector.write_all(b", ")?;
+                    }
+                }
+
+                // Class method call
+                ExpressionContent::InvokeClassMethod(class, method_name, _) => {
+                    if *method_name == "Constructor" {
+                        // HLSL has no struct constructors, call the function emitted along with the struct instead
+                        camel_to_snake_case(collector, &class.class_name)?;
+                        collector.write_all(b"_new")?;
+                    } else {
+                        // Static method call
+                        camel_to_snake_case(collector, &class.class_name)?;
+                        collector.write_all(b"_")?;
+                        camel_to_snake_case(collector, method_name)?;
+                    }
+                    collector.write_all(b"(")?;
+                }
+                _ => unreachable!(),
+            }
+
+            // Emit all arguments, comma-separated
+            for (i, (_argument_class, argument)) in arguments.iter().enumerate() {
+                if i > 0 {
+                    collector.write_all(b", ")?;
+                }
+                emit_expression(collector, argument, scalar_type)?;
+            }
+            collector.write_all(b")")?;
+        }
+
+        // Type conversion
+        ExpressionContent::Conversion(source_class, destination_class, inner_expression) => {
+            // Format: source_destination_into(expr)
+            camel_to_snake_case(collector, &source_class.class_name)?;
+            collector.write_all(b"_")?;
+            camel_to_snake_case(collector, &destination_class.class_name)?;
+            collector.write_all(b"_into(")?;
+            emit_expression(collector, inner_expression, scalar_type)?;
+            collector.write_all(b")")?;
+        }
+
+        // Ternary conditional operator (structs are handled in `VariableAssignment`)
+        ExpressionContent::Select(condition_expression, then_expression, else_expression) => {
+            collector.write_all(b"(")?;
+            emit_expression(collector, condition_expression, scalar_type)?;
+            collector.write_all(b") ? ")?;
+            emit_expression(collector, then_expression, scalar_type)?;
+            collector.write_all(b" : ")?;
+            emit_expression(collector, else_expression, scalar_type)?;
+        }
+
+        // Struct member access using .gN syntax
+        ExpressionContent::Access(inner_expression, array_index) => {
+            emit_expression(collector, inner_expression, scalar_type)?;
+            if !inner_expression.is_scalar() {
+                collector.write_fmt(format_args!(".g{}", array_index))?;
+            }
+        }
+
+        // HLSL vector swizzling (.xyzw)
+        ExpressionContent::Swizzle(inner_expression, indices) => {
+            emit_expression(collector, inner_expression, scalar_type)?;
+            collector.write_all(b".")?;
+            for component_index in indices.iter() {
+                collector.write_all(COMPONENT[*component_index].bytes().collect::<Vec<_>>().as_slice())?;
+            }
+        }
+
+        // Complex indexing for gathering components from potentially multiple vectors
+        ExpressionContent::Gather(inner_expression, indices) => {
+            if expression.size == 1 && inner_expression.is_scalar() {
+                // Simple case - just emit the inner expression
+                emit_expression(collector, inner_expression, scalar_type)?;
+            } else if expression.size > 1 && indices.len() == 1 {
+                // Vector constructors need all components, so a single component is splatted by a cast instead
+                collector.write_all(b"(")?;
+                emit_data_type(collector, &DataType::SimdVector(expression.size), scalar_type)?;
+                collector.write_all(b")(")?;
+                emit_expression(collector, inner_expression, scalar_type)?;
+                if !inner_expression.is_scalar() {
+                    let (array_index, component_index) = indices[0];
+                    collector.write_fmt(format_args!(".g{}", array_index))?;
+                    if inner_expression.size > 1 {
+                        collector.write_fmt(format_args!(".{}", COMPONENT[component_index]))?;
+                    }
+                }
+                collector.write_all(b")")?;
+            } else {
+                // Vector construction from components
+                if expression.size > 1 {
+                    emit_data_type(collector, &DataType::SimdVector(expression.size), scalar_type)?;
+                    collector.write_all(b"(")?;
+                }
+
+                // Generate each component access
+                for (i, (array_index, component_index)) in indices.iter().enumerate() {
+                    if i > 0 {
+                        collector.write_all(b", ")?;
+                    }
+                    emit_expression(collector, inner_expression, scalar_type)?;
+                    if !inner_expression.is_scalar() {
+                        // Access array element
+                        collector.write_fmt(format_args!(".g{}", array_index))?;
+                        if inner_expression.size > 1 {
+                            // Access component within vector
+                            collector.write_fmt(format_args!(".{}", COMPONENT[*component_index]))?;
+                        }
+                    }
+                }
+
+                if expression.size > 1 {
+                    collector.write_all(b")")?;
+                }
+            }
+        }
+
+        // Constant value emission
+        ExpressionContent::Constant(data_type, values) => match data_type {
+            DataType::Integer => collector.write_fmt(format_args!("{}", values[0] as f32))?,
+            DataType::SimdVector(_size) => {
+                if expression.size == 1 {
+                    // Scalar constant with decimal point
+                    collector.write_fmt(format_args!("{:.1}", values[0] as f32))?
+                } else if values.len() == 1 {
+                    // Splat a single value by a cast
+                    collector.write_all(b"(")?;
+                    emit_data_type(collector, &DataType::SimdVector(expression.size), scalar_type)?;
+                    collector.write_fmt(format_args!(")({:.1})", values[0] as f32))?
+                } else {
+                    // Vector constructor with components
+                    emit_data_type(collector, &DataType::SimdVector(expression.size), scalar_type)?;
+                    collector.write_fmt(format_args!(
+                        "({})",
+                        values.iter().map(|value| format!("{:.1}", *value as f32)).collect::<Vec<_>>().join(", ")
+                    ))?
+                }
+            }
+            _ => unreachable!(),
+        },
+
+        // Mathematical function
+        ExpressionContent::SquareRoot(inner_expression) => {
+            collector.write_all(b"sqrt(")?;
+            emit_expression(collector, inner_expression, scalar_type)?;
+            collector.write_all(b")")?;
+        }
+
+        // Binary operations
+        // Fused multiply-add, which the shader compiler contracts on its own
+        ExpressionContent::MultiplyAdd(a, b, c) => {
+            emit_expression(collector, c, scalar_type)?;
+            collector.write_all(b" + ")?;
+            emit_expression(collector, a, scalar_type)?;
+            collector.write_all(b" * ")?;
+            emit_expression(collector, b, scalar_type)?;
+        }
+
+        ExpressionContent::Add(lhs, rhs)
+        | ExpressionContent::Subtract(lhs, rhs)
+        | ExpressionContent::Multiply(lhs, rhs)
+        | ExpressionContent::Divide(lhs, rhs)
+        | ExpressionContent::LessThan(lhs, rhs)
+        | ExpressionContent::Equal(lhs, rhs)
+        | ExpressionContent::LogicAnd(lhs, rhs)
+        | ExpressionContent::BitShiftRight(lhs, rhs) => {
+            // Add parentheses for logical AND to ensure correct precedence
+            if let ExpressionContent::LogicAnd(_, _) = expression.content {
+                collector.write_all(b"(")?;
+            }
+
+            // Left operand
+            emit_expression(collector, lhs, scalar_type)?;
+
+            // Operator
+            collector.write_all(match expression.content {
+                ExpressionContent::Add(_, _) => b" + ",
+                ExpressionContent::Subtract(_, _) => b" - ",
+                ExpressionContent::Multiply(_, _) => b" * ",
+                ExpressionContent::Divide(_, _) => b" / ",
+                ExpressionContent::LessThan(_, _) => b" < ",
+                ExpressionContent::Equal(_, _) => b" == ",
+                ExpressionContent::LogicAnd(_, _) => b" & ",
+                ExpressionContent::BitShiftRight(_, _) => b" >> ",
+                _ => unreachable!(),
+            })?;
+
+            // Right operand
+            emit_expression(collector, rhs, scalar_type)?;
+            if let ExpressionContent::LogicAnd(_, _) = expression.content {
+                collector.write_all(b")")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Main function to emit HLSL code for an AST node
+pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, scalar_type: ScalarType, indentation: usize) -> std::io::Result<()> {
+    match ast_node {
+        AstNode::None => {}
+        AstNode::Preamble { .. } => {}
+
+        // Struct definition for multivector class
+        AstNode::ClassDefinition { class } => {
+            if class.is_scalar() {
+                return Ok(()); // Skip scalar classes
+            }
+
+            // Generate struct with fields for basis element groups
+            if let Some(doc) = &class.doc {
+                for line in doc.lines() {
+                    collector.write_fmt(format_args!("//{}{}\n", if line.is_empty() { "" } else { " " }, line))?;
+                }
+            }
+            collector.write_fmt(format_args!("struct {} {{\n", class.class_name))?;
+            for (i, group) in class.grouped_basis.iter().enumerate() {
+                // Comment showing the basis elements in this group
+                emit_indentation(collector, indentation + 1)?;
+                collector.write_all(b"// ")?;
+                for (i, element) in group.iter().enumerate() {
+                    if i > 0 {
+                        collector.write_all(b", ")?;
+                    }
+                    collector.write_fmt(format_args!("{}", element))?;
+                }
+                collector.write_all(b"\n")?;
+
+                // Field declaration using appropriate vector type
+                emit_indentation(collector, indentation + 1)?;
+                emit_data_type(collector, &DataType::SimdVector(group.len()), scalar_type)?;
+                collector.write_fmt(format_args!(" g{};\n", i))?;
+            }
+            emit_indentation(collector, indentation)?;
+            collector.write_all(b"};\n\n")?;
+
+            // Constructor function, as HLSL only supports initializer lists in declarations
+            emit_indentation(collector, indentation)?;
+            collector.write_fmt(format_args!("{} ", class.class_name))?;
+            camel_to_snake_case(collector, &class.class_name)?;
+            collector.write_all(b"_new(")?;
+            for (i, group) in class.grouped_basis.iter().enumerate() {
+                if i > 0 {
+                    collector.write_all(b", ")?;
+                }
+                emit_data_type(collector, &DataType::SimdVector(group.len()), scalar_type)?;
+                collector.write_fmt(format_args!(" g{}", i))?;
+            }
+            collector.write_all(b") {\n")?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_fmt(format_args!("{} result = {{ ", class.class_name))?;
+            for i in 0..class.grouped_basis.len() {
+                if i > 0 {
+                    collector.write_all(b", ")?;
+                }
+                collector.write_fmt(format_args!("g{}", i))?;
+            }
+            collector.write_all(b" };\n")?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_all(b"return result;\n")?;
+            emit_indentation(collector, indentation)?;
+            collector.write_all(b"}\n\n")?;
+        }
+
+        // Return statement
+        AstNode::ReturnStatement { expression } => {
+            collector.write_all(b"return ")?;
+            emit_expression(collector, expression, scalar_type)?;
+            collector.write_all(b";\n")?;
+        }
+
+        // Selection between structs, which the ternary operator does not support: Declare the else value and overwrite it conditionally
+        AstNode::VariableAssignment {
+            name,
+            data_type: Some(data_type @ DataType::MultiVector(class)),
+            expression,
+        } if !class.is_scalar() && matches!(expression.content, ExpressionContent::Select(..)) => {
+            let (condition_expression, then_expression, else_expression) = match &expression.content {
+                ExpressionContent::Select(condition_expression, then_expression, else_expression) => {
+                    (condition_expression, then_expression, else_expression)
+                }
+                _ => unreachable!(),
+            };
+            emit_data_type(collector, data_type, scalar_type)?;
+            collector.write_fmt(format_args!(" {} = ", name))?;
+            emit_expression(collector, else_expression, scalar_type)?;
+            collector.write_all(b";\n")?;
+            emit_indentation(collector, indentation)?;
+            collector.write_all(b"if(")?;
+            emit_expression(collector, condition_expression, scalar_type)?;
+            collector.write_all(b") {\n")?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_fmt(format_args!("{} = ", name))?;
+            emit_expression(collector, then_expression, scalar_type)?;
+            collector.write_all(b";\n")?;
+            emit_indentation(collector, indentation)?;
+            collector.write_all(b"}\n")?;
+        }
+
+        // Variable declaration/assignment
+        AstNode::VariableAssignment { name, data_type, expression } => {
+            if let Some(data_type) = data_type {
+                // Include type for declarations
+                emit_data_type(collector, data_type, scalar_type)?;
+                collector.write_all(b" ")?;
+            }
+            collector.write_fmt(format_args!("{} = ", name))?;
+            emit_expression(collector, expression, scalar_type)?;
+            collector.write_all(b";\n")?;
+        }
+
+        // If and while blocks
+        AstNode::IfThenBlock { condition, body } | AstNode::WhileLoopBlock { condition, body } => {
+            collector.write_all(match &ast_node {
+                AstNode::IfThenBlock { .. } => b"if",
+                AstNode::WhileLoopBlock { .. } => b"while",
+                _ => unreachable!(),
+            })?;
+
+            // Condition and block opening
+            collector.write_all(b"(")?;
+            emit_expression(collector, condition, scalar_type)?;
+            collector.write_all(b") {\n")?;
+
+            // Body statements
+            for statement in body.iter() {
+                emit_indentation(collector, indentation + 1)?;
+                emit_code(collector, statement, scalar_type, indentation + 1)?;
+            }
+
+            // Block closing
+            emit_indentation(collector, indentation)?;
+            collector.write_all(b"}\n")?;
+        }
+
+        // Function definition
+        AstNode::TraitImplementation { result, parameters, body } => {
+            // Return type and function name construction
+            emit_data_type(collector, &result.data_type, scalar_type)?;
+            collector.write_all(b" ")?;
+
+            // Generate function name based on parameter count and types
+            match parameters.len() {
+                0 => camel_to_snake_case(collector, &result.multi_vector_class().class_name)?,
+                1 if result.name == "Into" => {
+                    // Special case for conversion functions
+                    camel_to_snake_case(collector, &parameters[0].multi_vector_class().class_name)?;
+                    collector.write_all(b"_")?;
+                    camel_to_snake_case(collector, &result.multi_vector_class().class_name)?;
+                }
+                1 => camel_to_snake_case(collector, &parameters[0].multi_vector_class().class_name)?,
+                2 if !matches!(parameters[1].data_type, DataType::MultiVector(_)) => {
+                    // Method with one multivector and one non-multivector parameter
+                    camel_to_snake_case(collector, &parameters[0].multi_vector_class().class_name)?
+                }
+                2 => {
+                    // Method with two multivector parameters
+                    camel_to_snake_case(collector, &parameters[0].multi_vector_class().class_name)?;
+                    collector.write_all(b"_")?;
+                    camel_to_snake_case(collector, &parameters[1].multi_vector_class().class_name)?;
+                }
+                _ => unreachable!(),
+            }
+            collector.write_all(b"_")?;
+            camel_to_snake_case(collector, result.name)?;
+
+            // Function parameters
+            collector.write_all(b"(")?;
+            for (i, parameter) in parameters.iter().enumerate() {
+                if i > 0 {
+                    collector.write_all(b", ")?;
+                }
+                emit_data_type(collector, &parameter.data_type, scalar_type)?;
+                collector.write_fmt(format_args!(" {}", parameter.name))?;
+            }
+            collector.write_all(b") {\n")?;
+
+            // Function body
+            for statement in body.iter() {
+                emit_indentation(collector, indentation + 1)?;
+                emit_code(collector, statement, scalar_type, indentation + 1)?;
+            }
+
+            // Function closing
+            emit_indentation(collector, indentation)?;
+            collector.write_all(b"}\n\n")?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{assert_balanced_brackets, block_lines, emit::Target, emit_preset};
+
+    #[test]
+    fn ppga3d_structure() {
+        let code = emit_preset("ppga3d", Target::Hlsl).unwrap();
+        assert_balanced_brackets(&code);
+
+        // One struct of `floatN` groups per class, scalars are plain floats
+        assert!(!code.contains("struct Scalar"));
+        for (class, members) in [
+            ("MultiVector", &["float4 g0;", "float4 g1;", "float4 g2;", "float4 g3;"][..]),
+            ("Rotor", &["float4 g0;"]),
+            ("Point", &["float4 g0;"]),
+            ("IdealPoint", &["float3 g0;"]),
+            ("Plane", &["float4 g0;"]),
+            ("Line", &["float3 g0;", "float3 g1;"]),
+            ("Translator", &["float4 g0;"]),
+            ("Motor", &["float4 g0;", "float4 g1;"]),
+            ("PointAndPlane", &["float4 g0;", "float4 g1;"]),
+        ] {
+            assert_eq!(block_lines(&code, &format!("struct {} {{", class), "};"), members, "{}", class);
+        }
+
+        // Functions are named after the classes in snake case and the trait
+        for signature in [
+            "Line line_new(float3 g0, float3 g1) {",
+            "PointAndPlane point_and_plane_new(float4 g0, float4 g1) {",
+            "float scalar_one() {",
+            "Motor motor_motor_geometric_product(Motor self, Motor other) {",
+            "Point motor_point_transformation(Motor self, Point other) {",
+            "float line_ideal_norm(Line self) {",
+        ] {
+            assert!(code.lines().any(|line| line == signature), "`{}` is missing", signature);
+        }
+    }
+    #[test]
+    fn ppga3d_f64_structure() {
+        let code = emit_preset("ppga3d_f64", Target::Hlsl).unwrap();
+        assert_balanced_brackets(&code);
+        assert_eq!(block_lines(&code, "struct Line {", "};"), ["double3 g0;", "double3 g1;"]);
+        assert!(code.lines().any(|line| line == "Line line_new(double3 g0, double3 g1) {"));
+    }
+}
@@ -0,0 +1,537 @@
+//! Code generator which turns descriptors of geometric algebras into Rust, GLSL, WGSL, HLSL, MSL, C, CUDA and Python source code
+//!
+//! The pipeline consists of these stages, which can also be used individually to write custom passes and backends:
+//! - [parse] / [config]: Read a [Descriptor] from a compact string or a TOML file
+//! - [algebra]: [GeometricAlgebra] generates the multiplication tables ([Product::products], [Involution::involutions])
+//! - [compile]: Builds the [AstNode]s of the trait implementations for the classes in a [MultiVectorClassRegistry]
+//! - [emit], [rust], [rust_batch], [glsl], [wgsl], [hlsl], [msl], [c], [cuda], [python]: Serialize the AST to source code, driven by an [Emitter]
+//!
+//! [emit_algebra] runs the compiler and hands every [AstNode] to a callback,
+//! so that a custom backend or pass does not need to replicate which traits are derived from which:
+//!
+//! ```no_run
+//! use geometric_algebra_codegen::{emit_algebra, parse_descriptor, rust, AstNode, ScalarType};
+//!
+//! let descriptor = parse_descriptor("vga2d:1,1;Scalar:1;Rotor:1,e01;Vector:e0,e1").unwrap();
+//! let mut collector = std::io::stdout();
+//! emit_algebra(descriptor, "geometric_algebra", |ast_node: &AstNode| {
+//!     // Skip the class definitions, e.g. because they are provided elsewhere
+//!     if let AstNode::ClassDefinition { .. } = ast_node {
+//!         return Ok(());
+//!     }
+//!     rust::emit_code(&mut collector, ast_node, ScalarType::F32, 0)
+//! })
+//! .unwrap();
+//! ```
+//!
+//! Besides the `codegen` binary, this library can be used from a `build.rs` to generate an algebra inside of a downstream crate:
+//!
+//! ```no_run
+//! let out_dir = std::env::var("OUT_DIR").unwrap();
+//! geometric_algebra_codegen::generate("cga2d:1,1,1,-1;Scalar:1;Rotor:1,e12", &out_dir).unwrap();
+//! ```
+pub mod algebra;
+pub mod ast;
+pub mod c;
+pub mod compile;
+pub mod config;
+pub mod cuda;
+pub mod emit;
+pub mod glsl;
+pub mod hlsl;
+pub mod msl;
+pub mod parse;
+pub mod python;
+pub mod rust;
+pub mod rust_batch;
+pub mod wgsl;
+
+pub use crate::{
+    algebra::{BasisElement, GeometricAlgebra, Involution, MultiVectorClass, MultiVectorClassRegistry, Product},
+    ast::{AstNode, DataType, Expression, ExpressionContent, Parameter, ScalarType},
+    config::parse_config,
+    emit::{Emitter, Target},
+    parse::{parse_descriptor, Descriptor, ParseError},
+};
+
+/// Error of [generate] and [generate_from_config]
+#[derive(Debug)]
+pub enum Error {
+    Descriptor(ParseError), // The descriptor is malformed
+    Io(std::io::Error),     // A file could not be read or written
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Descriptor(error) => error.fmt(formatter),
+            Self::Io(error) => error.fmt(formatter),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ParseError> for Error {
+    fn from(error: ParseError) -> Self {
+        Self::Descriptor(error)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// Writes the Cayley table of the algebra with the given generator squares
+pub fn write_cayley_table<W: std::io::Write>(collector: &mut W, generator_squares: &[isize]) -> std::io::Result<()> {
+    let algebra = GeometricAlgebra { generator_squares };
+    let basis = algebra.sorted_basis();
+    for b in basis.iter() {
+        for a in basis.iter() {
+            write!(collector, "{:1$} ", BasisElement::product(a, b, &algebra), generator_squares.len() + 2)?;
+        }
+        writeln!(collector)?;
+    }
+    Ok(())
+}
+
+/// Compiles the classes and trait implementations of an algebra and passes each resulting [AstNode] to `emit`
+///
+/// `crate_path` is the path of the `geometric_algebra` crate from the perspective of the generated code:
+/// `crate` inside of the `geometric_algebra` crate itself and `geometric_algebra` in downstream crates.
+pub fn emit_algebra<F: FnMut(&AstNode) -> std::io::Result<()>>(descriptor: Descriptor, crate_path: &str, mut emit: F) -> std::io::Result<()> {
+    let Descriptor {
+        generator_squares,
+        traits,
+        classes,
+        ..
+    } = descriptor;
+    let enabled = |name: &str| match &traits {
+        Some(traits) => traits.contains(name),
+        None => true,
+    };
+
+    // Create the algebra from the generator squares
+    let algebra = GeometricAlgebra {
+        generator_squares: generator_squares.as_slice(),
+    };
+
+    // Precompute involutions and products for the given algebra
+    let involutions = Involution::involutions(&algebra);
+    let products = Product::products(&algebra);
+
+    // Register the user-defined multivector classes
+    let mut registry = MultiVectorClassRegistry::default();
+    for class in classes {
+        registry.register(class);
+    }
+
+    // Emit preamble (e.g., `use`, common functions)
+    emit(&AstNode::Preamble { crate_path })?;
+
+    // Emit class definitions for all registered multivector types
+    for class in registry.classes.iter() {
+        emit(&AstNode::ClassDefinition { class })?;
+    }
+
+    // Emit trait impls
+    let mut trait_implementations = std::collections::BTreeMap::new();
+    for class_a in registry.classes.iter() {
+        let parameter_a = Parameter {
+            name: "self",
+            data_type: DataType::MultiVector(class_a),
+        };
+        let mut single_trait_implementations = std::collections::BTreeMap::new();
+
+        // Emit constants like Zero and One
+        for name in &["Zero", "One"] {
+            let ast_node = class_a.constant(name);
+            if enabled(name) {
+                emit(&ast_node)?;
+            }
+            if ast_node != AstNode::None {
+                single_trait_implementations.insert(name.to_string(), ast_node);
+            }
+        }
+
+        // Emit involutions (like Reverse, GradeInvolution)
+        for (name, involution) in involutions.iter() {
+            let ast_node = MultiVectorClass::involution(name, involution, &parameter_a, &registry, None);
+            if enabled(name) {
+                emit(&ast_node)?;
+            }
+            if ast_node != AstNode::None {
+                single_trait_implementations.insert(name.to_string(), ast_node);
+            }
+        }
+
+        // Emit pair trait impls
+        let mut pair_trait_implementations = std::collections::BTreeMap::new();
+        for class_b in registry.classes.iter() {
+            let mut trait_implementations = std::collections::BTreeMap::new();
+            let parameter_b = Parameter {
+                name: "other",
+                data_type: DataType::MultiVector(class_b),
+            };
+
+            // Emit conversions between different mv classes
+            if class_a != class_b {
+                let name = "Into";
+                let ast_node = MultiVectorClass::involution(name, &Involution::projection(class_b), &parameter_a, &registry, Some(class_b));
+                if enabled(name) {
+                    emit(&ast_node)?;
+                }
+                if ast_node != AstNode::None {
+                    trait_implementations.insert(name.to_string(), ast_node);
+                }
+            }
+
+            // Emit element-wise Add/Sub
+            for name in &["Add", "Sub"] {
+                let ast_node = MultiVectorClass::element_wise(name, &parameter_a, &parameter_b, &registry);
+                if enabled(name) {
+                    emit(&ast_node)?;
+                }
+                if ast_node != AstNode::None {
+                    trait_implementations.insert(name.to_string(), ast_node);
+                }
+            }
+
+            // Emit element-wise Mul/Div (only for same class combinations)
+            if class_a == class_b {
+                for name in &["Mul", "Div"] {
+                    let ast_node = MultiVectorClass::element_wise(name, &parameter_a, &parameter_b, &registry);
+                    if enabled(name) {
+                        emit(&ast_node)?;
+                    }
+                    if ast_node != AstNode::None {
+                        trait_implementations.insert(name.to_string(), ast_node);
+                    }
+                }
+            }
+
+            // Emit all defined algebraic products (geometric, inner, outer, etc.)
+            for (name, product) in products.iter() {
+                let ast_node = MultiVectorClass::product(name, product, &parameter_a, &parameter_b, &registry);
+                if enabled(name) {
+                    emit(&ast_node)?;
+                }
+                if ast_node != AstNode::None {
+                    trait_implementations.insert(name.to_string(), ast_node);
+                }
+            }
+
+            // Store pair trait impls
+            pair_trait_implementations.insert(
+                parameter_b.multi_vector_class().class_name.clone(),
+                (parameter_b.clone(), trait_implementations),
+            );
+        }
+
+        // Derive magnitude and squared magnitude if scalar product and reversal exist
+        for (parameter_b, pair_trait_implementations) in pair_trait_implementations.values() {
+            if let Some(scalar_product) = pair_trait_implementations.get("ScalarProduct") {
+                if let Some(reversal) = single_trait_implementations.get("Reversal") {
+                    if parameter_a.multi_vector_class() == parameter_b.multi_vector_class() {
+                        let squared_magnitude =
+                            MultiVectorClass::derive_squared_magnitude("SquaredMagnitude", scalar_product, reversal, &parameter_a);
+                        if enabled("SquaredMagnitude") {
+                            emit(&squared_magnitude)?;
+                        }
+
+                        let magnitude = MultiVectorClass::derive_magnitude("Magnitude", &squared_magnitude, &parameter_a);
+                        if enabled("Magnitude") {
+                            emit(&magnitude)?;
+                        }
+
+                        single_trait_implementations.insert(result_of_trait!(squared_magnitude).name.to_string(), squared_magnitude);
+                        single_trait_implementations.insert(result_of_trait!(magnitude).name.to_string(), magnitude);
+                    }
+                }
+            }
+        }
+
+        // Derive the norms of the bulk and the weight in degenerate metrics, in which the magnitude ignores the bulk
+        if algebra.generator_squares.contains(&0) {
+            for (name, bulk) in [("BulkNorm", true), ("IdealNorm", true), ("WeightNorm", false)] {
+                let ast_node = MultiVectorClass::derive_degenerate_norm(name, bulk, &parameter_a, &registry, &algebra);
+                if enabled(name) {
+                    emit(&ast_node)?;
+                }
+            }
+        }
+
+        // Derive scale, signum, inverse if applicable for scalar * multivector
+        for (parameter_b, pair_trait_implementations) in pair_trait_implementations.values() {
+            if let Some(geometric_product) = pair_trait_implementations.get("GeometricProduct") {
+                if parameter_b.data_type.is_scalar() {
+                    if !parameter_a.data_type.is_scalar() {
+                        let scale = MultiVectorClass::derive_scale("Mul", geometric_product, &parameter_a, parameter_b);
+                        if enabled("Mul") {
+                            emit(&scale)?;
+                        }
+                    }
+                    if let Some(magnitude) = single_trait_implementations.get("Magnitude") {
+                        let signum = MultiVectorClass::derive_signum("Signum", geometric_product, magnitude, &parameter_a);
+                        if enabled("Signum") {
+                            emit(&signum)?;
+                        }
+                        single_trait_implementations.insert(result_of_trait!(signum).name.to_string(), signum);
+                    }
+                    if let Some(squared_magnitude) = single_trait_implementations.get("SquaredMagnitude") {
+                        if let Some(reversal) = single_trait_implementations.get("Reversal") {
+                            let inverse = MultiVectorClass::derive_inverse("Inverse", geometric_product, squared_magnitude, reversal, &parameter_a);
+                            if enabled("Inverse") {
+                                emit(&inverse)?;
+                            }
+                            single_trait_implementations.insert(result_of_trait!(inverse).name.to_string(), inverse);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Derive the exponential of bivectors, the logarithm, square root and normalization of rotors
+        let exponential = MultiVectorClass::derive_exponential("Exp", &parameter_a, &registry, &algebra);
+        if enabled("Exp") {
+            emit(&exponential)?;
+        }
+        if exponential != AstNode::None {
+            single_trait_implementations.insert(result_of_trait!(exponential).name.to_string(), exponential);
+        }
+        if let Some(magnitude) = single_trait_implementations.get("Magnitude") {
+            let logarithm = MultiVectorClass::derive_logarithm("Ln", magnitude, &parameter_a, &registry, &algebra);
+            if enabled("Ln") {
+                emit(&logarithm)?;
+            }
+            if logarithm != AstNode::None {
+                single_trait_implementations.insert(result_of_trait!(logarithm).name.to_string(), logarithm);
+            }
+        }
+
+        let square_root = MultiVectorClass::derive_square_root("Sqrt", &parameter_a, &registry, &algebra);
+        if enabled("Sqrt") {
+            emit(&square_root)?;
+        }
+        let normalization = MultiVectorClass::derive_normalization("Normalize", &parameter_a, &registry, &algebra);
+        if enabled("Normalize") {
+            emit(&normalization)?;
+        }
+
+        // Store all single and pair trait impls for class A
+        trait_implementations.insert(
+            parameter_a.multi_vector_class().class_name.clone(),
+            (parameter_a.clone(), single_trait_implementations, pair_trait_implementations),
+        );
+    }
+
+    // Final pass: derive higher-level traits like powi, powf, quotient, transformation
+    for (parameter_a, single_trait_implementations, pair_trait_implementations) in trait_implementations.values() {
+        // Derive Powf = exp(ln(a) * b) when the exponential of the logarithm is of the same type again
+        if let Some(logarithm) = single_trait_implementations.get("Ln") {
+            if let Some(b_trait_implementations) = trait_implementations.get(&result_of_trait!(logarithm).multi_vector_class().class_name) {
+                if let Some(exponential) = b_trait_implementations.1.get("Exp") {
+                    for (parameter_b, b_pair_trait_implementations) in b_trait_implementations.2.values() {
+                        if let Some(geometric_product) = b_pair_trait_implementations.get("GeometricProduct") {
+                            if parameter_b.data_type.is_scalar()
+                                && result_of_trait!(geometric_product).data_type == result_of_trait!(logarithm).data_type
+                                && result_of_trait!(exponential).data_type == parameter_a.data_type
+                            {
+                                let power_of_float = MultiVectorClass::derive_power_of_float(
+                                    "Powf",
+                                    geometric_product,
+                                    exponential,
+                                    logarithm,
+                                    parameter_a,
+                                    &Parameter {
+                                        name: "exponent",
+                                        data_type: parameter_b.data_type.clone(),
+                                    },
+                                );
+                                if enabled("Powf") {
+                                    emit(&power_of_float)?;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        for (parameter_b, pair_trait_implementations) in pair_trait_implementations.values() {
+            if let Some(geometric_product) = pair_trait_implementations.get("GeometricProduct") {
+                let geometric_product_result = result_of_trait!(geometric_product);
+
+                // Derive Powi when all types are the same
+                if parameter_a.multi_vector_class() == parameter_b.multi_vector_class()
+                    && geometric_product_result.multi_vector_class() == parameter_a.multi_vector_class()
+                {
+                    if let Some(constant_one) = single_trait_implementations.get("One") {
+                        if let Some(inverse) = single_trait_implementations.get("Inverse") {
+                            let power_of_integer = MultiVectorClass::derive_power_of_integer(
+                                "Powi",
+                                geometric_product,
+                                constant_one,
+                                inverse,
+                                parameter_a,
+                                &Parameter {
+                                    name: "exponent",
+                                    data_type: DataType::Integer,
+                                },
+                            );
+                            if enabled("Powi") {
+                                emit(&power_of_integer)?;
+                            }
+                        }
+                    }
+                }
+
+                // Derive GeometricQuotient = a * b⁻¹
+                if let Some(b_trait_implementations) = trait_implementations.get(&parameter_b.multi_vector_class().class_name) {
+                    if let Some(inverse) = b_trait_implementations.1.get("Inverse") {
+                        let division = MultiVectorClass::derive_division("GeometricQuotient", geometric_product, inverse, parameter_a, parameter_b);
+                        if enabled("GeometricQuotient") {
+                            emit(&division)?;
+                        }
+                    }
+                }
+
+                // Derive Transformation = a * b * a⁻¹
+                if let Some(reversal) = single_trait_implementations.get("Reversal") {
+                    if let Some(b_trait_implementations) = trait_implementations.get(&geometric_product_result.multi_vector_class().class_name) {
+                        if let Some(b_pair_trait_implementations) = b_trait_implementations.2.get(&parameter_a.multi_vector_class().class_name) {
+                            if let Some(geometric_product_2) = b_pair_trait_implementations.1.get("GeometricProduct") {
+                                let geometric_product_2_result = result_of_trait!(geometric_product_2);
+                                if let Some(c_trait_implementations) =
+                                    trait_implementations.get(&geometric_product_2_result.multi_vector_class().class_name)
+                                {
+                                    if let Some(c_pair_trait_implementations) =
+                                        c_trait_implementations.2.get(&parameter_b.multi_vector_class().class_name)
+                                    {
+                                        let transformation = MultiVectorClass::derive_sandwich_product(
+                                            "Transformation",
+                                            geometric_product,
+                                            geometric_product_2,
+                                            reversal,
+                                            c_pair_trait_implementations.1.get("Into"),
+                                            parameter_a,
+                                            parameter_b,
+                                        );
+                                        if enabled("Transformation") {
+                                            emit(&transformation)?;
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Generates `<out_dir>/<algebra_name>.rs` from a compact descriptor string and returns its path
+///
+/// The generated file is meant to be included into a module of a downstream crate, which depends on `geometric_algebra`:
+///
+/// ```ignore
+/// #[allow(clippy::assign_op_pattern, clippy::from_over_into)]
+/// mod cga2d {
+///     include!(concat!(env!("OUT_DIR"), "/cga2d.rs"));
+/// }
+/// ```
+pub fn generate<P: AsRef<std::path::Path>>(descriptor: &str, out_dir: P) -> Result<std::path::PathBuf, Error> {
+    write_rust(parse_descriptor(descriptor)?, out_dir.as_ref())
+}
+
+/// Generates `<out_dir>/<algebra_name>.rs` from a TOML descriptor file and returns its path, see [generate]
+pub fn generate_from_config<P: AsRef<std::path::Path>, Q: AsRef<std::path::Path>>(path: P, out_dir: Q) -> Result<std::path::PathBuf, Error> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path)?;
+    write_rust(parse_config(&path.display().to_string(), &text)?, out_dir.as_ref())
+}
+
+fn write_rust(descriptor: Descriptor, out_dir: &std::path::Path) -> Result<std::path::PathBuf, Error> {
+    let path = out_dir.join(&descriptor.algebra_name).with_extension(Target::Rust.extension());
+    let mut emitter = Emitter::new(&[Target::Rust], descriptor.scalar_type);
+    emit_algebra(descriptor, "geometric_algebra", |ast_node| emitter.emit(ast_node))?;
+    std::fs::create_dir_all(out_dir)?;
+    std::fs::write(&path, &emitter.collectors[0].1)?;
+    Ok(path)
+}
+
+/// Emits the code of the prebuilt algebra `algebras/<algebra_name>.toml`, unless the target does not support it
+#[cfg(test)]
+pub(crate) fn emit_preset(algebra_name: &str, target: Target) -> Option<String> {
+    let path = format!("{}/algebras/{}.toml", env!("CARGO_MANIFEST_DIR"), algebra_name);
+    let descriptor = parse_config(&path, &std::fs::read_to_string(&path).unwrap()).unwrap();
+    let max_group_size = descriptor
+        .classes
+        .iter()
+        .flat_map(|class| class.grouped_basis.iter())
+        .map(|group| group.len())
+        .max();
+    if !target.supports(descriptor.scalar_type) || !target.supports_group_size(max_group_size.unwrap_or(1)) {
+        return None;
+    }
+    let mut emitter = Emitter::new(&[target], descriptor.scalar_type);
+    emit_algebra(descriptor, "crate", |ast_node| emitter.emit(ast_node)).unwrap();
+    Some(String::from_utf8(emitter.collectors.remove(0).1).unwrap())
+}
+
+/// Emits the code of every prebuilt algebra in `algebras/` which the target supports, as pairs of algebra name and code
+#[cfg(test)]
+pub(crate) fn emit_presets(target: Target) -> Vec<(String, String)> {
+    let mut algebra_names = std::fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/algebras"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path().file_stem().unwrap().to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    algebra_names.sort();
+    algebra_names
+        .into_iter()
+        .filter_map(|algebra_name| emit_preset(&algebra_name, target).map(|code| (algebra_name, code)))
+        .collect()
+}
+
+/// Checks that all brackets of the generated code are closed in the right order, ignoring line comments
+#[cfg(test)]
+pub(crate) fn assert_balanced_brackets(code: &str) {
+    let mut open = Vec::new();
+    for (line_number, line) in code.lines().enumerate() {
+        for c in line.split("//").next().unwrap().chars() {
+            match c {
+                '(' | '[' | '{' => open.push(c),
+                ')' | ']' | '}' => {
+                    let expected = match c {
+                        ')' => '(',
+                        ']' => '[',
+                        _ => '{',
+                    };
+                    assert_eq!(open.pop(), Some(expected), "unbalanced `{}` in line {}: {}", c, line_number + 1, line);
+                }
+                _ => {}
+            }
+        }
+    }
+    assert!(open.is_empty(), "unclosed {:?}", open);
+}
+
+/// Returns the lines between the line `head` and the next line `tail`, without comments, and checks that `head` occurs exactly once
+#[cfg(test)]
+pub(crate) fn block_lines<'a>(code: &'a str, head: &str, tail: &str) -> Vec<&'a str> {
+    let mut lines = code.lines();
+    assert!(lines.any(|line| line == head), "`{}` is missing", head);
+    let block = lines
+        .by_ref()
+        .take_while(|line| *line != tail)
+        .map(str::trim)
+        .filter(|line| !line.starts_with("//"))
+        .collect();
+    assert!(!lines.any(|line| line == head), "`{}` occurs more than once", head);
+    block
+}
@@ -0,0 +1,302 @@
+/// Parser for the compact descriptor string `name:squares[:scalar_type];Class:elements|elements;...`
+use crate::{
+    algebra::{BasisElement, GeometricAlgebra, MultiVectorClass},
+    ast::ScalarType,
+};
+
+/// An algebra and its multi vector classes, as described by the user
+pub struct Descriptor {
+    pub algebra_name: String,
+    pub generator_squares: Vec<isize>,
+    pub scalar_type: ScalarType,
+    pub traits: Option<std::collections::BTreeSet<String>>, // Traits to emit, `None` emits all of them
+    pub classes: Vec<MultiVectorClass>,
+}
+
+/// Error pointing at the offending token of a descriptor
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub origin: String,  // Where the descriptor came from (e.g. a file path)
+    pub text: String,    // The complete descriptor
+    pub offset: usize,   // Byte offset of the offending token
+    pub token: String,   // The offending token (empty if something is missing)
+    pub message: String, // What is wrong with the token
+}
+
+impl std::fmt::Display for ParseError {
+    /// Prints the message followed by the line of the offending token, which is underlined by carets
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let line_start = self.text[..self.offset].rfind('\n').map_or(0, |index| index + 1);
+        let line_end = self.text[self.offset..].find('\n').map_or(self.text.len(), |index| self.offset + index);
+        let line_number = self.text[..line_start].matches('\n').count() + 1;
+        let column = self.text[line_start..self.offset].chars().count();
+        writeln!(formatter, "error: {}", self.message)?;
+        writeln!(formatter, "  --> {}:{}:{}", self.origin, line_number, column + 1)?;
+        writeln!(formatter, "   | {}", &self.text[line_start..line_end])?;
+        write!(formatter, "   | {}{}", " ".repeat(column), "^".repeat(self.token.chars().count().max(1)))
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Splits `token` at `separator` and pairs each piece with its byte offset in the descriptor
+fn split_with_offsets(token: &str, offset: usize, separator: char) -> impl Iterator<Item = (usize, &str)> {
+    token.split(separator).scan(offset, move |next_offset, piece| {
+        let piece_offset = *next_offset;
+        *next_offset += piece.len() + separator.len_utf8();
+        Some((piece_offset, piece))
+    })
+}
+
+/// Checks that a name can be used as an identifier in all target languages
+pub fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_') && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Parses a descriptor string into a [Descriptor]
+pub fn parse_descriptor(descriptor: &str) -> Result<Descriptor, ParseError> {
+    let error = |offset: usize, token: &str, message: String| ParseError {
+        origin: "descriptor".to_string(),
+        text: descriptor.to_string(),
+        offset,
+        token: token.to_string(),
+        message,
+    };
+
+    // Split descriptor by `;` into algebra and multivector class descriptors
+    let mut descriptor_iter = split_with_offsets(descriptor, 0, ';');
+    let (algebra_offset, algebra_descriptor) = descriptor_iter.next().unwrap();
+
+    // Parse algebra descriptor: `name:squares` or `name:squares:scalar_type`
+    let mut algebra_descriptor_iter = split_with_offsets(algebra_descriptor, algebra_offset, ':');
+    let (name_offset, algebra_name) = algebra_descriptor_iter.next().unwrap();
+    if !is_identifier(algebra_name) {
+        return Err(error(name_offset, algebra_name, "expected an algebra name".to_string()));
+    }
+    let (squares_offset, squares) = algebra_descriptor_iter.next().ok_or_else(|| {
+        error(
+            name_offset + algebra_name.len(),
+            "",
+            "expected `:` followed by a comma-separated list of generator squares".to_string(),
+        )
+    })?;
+
+    // Parse comma-separated list of generator squares into `Vec<isize>`
+    let mut generator_squares = Vec::new();
+    for (square_offset, square) in split_with_offsets(squares, squares_offset, ',') {
+        match square.parse::<isize>() {
+            Ok(value) if (-1..=1).contains(&value) => generator_squares.push(value),
+            _ => {
+                return Err(error(
+                    square_offset,
+                    square,
+                    "expected a generator square of `-1`, `0` or `1`".to_string(),
+                ))
+            }
+        }
+    }
+    if generator_squares.len() > std::mem::size_of::<crate::algebra::BasisElementIndex>() * 8 {
+        return Err(error(
+            squares_offset,
+            squares,
+            format!("expected at most 16 generators, found {}", generator_squares.len()),
+        ));
+    }
+
+    // Parse optional scalar type, defaults to `f32`
+    let scalar_type = match algebra_descriptor_iter.next() {
+        Some((scalar_type_offset, name)) => {
+            ScalarType::parse(name).ok_or_else(|| error(scalar_type_offset, name, "expected a scalar type of `f32` or `f64`".to_string()))?
+        }
+        None => ScalarType::F32,
+    };
+    if let Some((offset, token)) = algebra_descriptor_iter.next() {
+        return Err(error(offset, token, "unexpected token after the scalar type".to_string()));
+    }
+
+    // Parse the user-defined multivector classes: `Name:elements|elements`
+    let algebra = GeometricAlgebra {
+        generator_squares: generator_squares.as_slice(),
+    };
+    let mut classes: Vec<MultiVectorClass> = Vec::new();
+    for (class_offset, class_descriptor) in descriptor_iter {
+        let mut class_descriptor_iter = split_with_offsets(class_descriptor, class_offset, ':');
+        let (class_name_offset, class_name) = class_descriptor_iter.next().unwrap();
+        let (groups_offset, groups) = class_descriptor_iter.next().ok_or_else(|| {
+            error(
+                class_name_offset + class_name.len(),
+                "",
+                "expected `:` followed by a comma-separated list of basis elements".to_string(),
+            )
+        })?;
+        if let Some((offset, token)) = class_descriptor_iter.next() {
+            return Err(error(offset, token, "unexpected token after the basis elements".to_string()));
+        }
+        let groups = split_with_offsets(groups, groups_offset, '|')
+            .map(|group| (group, split_with_offsets(group.1, group.0, ',').collect()))
+            .collect::<Vec<_>>();
+        let class = parse_class(
+            &classes,
+            (class_name_offset, class_name),
+            None,
+            &groups,
+            |element_name| BasisElement::parse(element_name, &algebra),
+            &error,
+        )?;
+        classes.push(class);
+    }
+
+    Ok(Descriptor {
+        algebra_name: algebra_name.to_string(),
+        generator_squares,
+        scalar_type,
+        traits: None,
+        classes,
+    })
+}
+
+/// A piece of a descriptor and its byte offset
+pub type Token<'a> = (usize, &'a str);
+
+/// Builds a [MultiVectorClass] from its name and groups of basis elements
+///
+/// Rejects everything the emitters can not handle: Duplicate names and elements as well as groups which do not fit into SIMD vectors.
+/// Classes may have the same basis elements (like spheres and planes in CGA), see [MultiVectorClassRegistry::get](crate::algebra::MultiVectorClassRegistry::get).
+pub fn parse_class<E, F>(
+    classes: &[MultiVectorClass],
+    class_name: Token,
+    doc: Option<String>,
+    groups: &[(Token, Vec<Token>)],
+    parse_element: F,
+    error: &E,
+) -> Result<MultiVectorClass, ParseError>
+where
+    E: Fn(usize, &str, String) -> ParseError,
+    F: Fn(&str) -> Result<BasisElement, String>,
+{
+    let (class_name_offset, class_name) = class_name;
+    if !is_identifier(class_name) {
+        return Err(error(class_name_offset, class_name, "expected a class name".to_string()));
+    }
+    if classes.iter().any(|class| class.class_name == class_name) {
+        return Err(error(
+            class_name_offset,
+            class_name,
+            format!("class `{}` is defined more than once", class_name),
+        ));
+    }
+    let mut grouped_basis = Vec::new();
+    for ((group_offset, group), element_names) in groups.iter() {
+        let mut elements = Vec::new();
+        for (element_offset, element_name) in element_names.iter() {
+            let element = parse_element(element_name).map_err(|message| error(*element_offset, element_name, message))?;
+            if grouped_basis
+                .iter()
+                .chain(std::iter::once(&elements))
+                .flatten()
+                .any(|other: &BasisElement| other.index == element.index)
+            {
+                return Err(error(
+                    *element_offset,
+                    element_name,
+                    format!("basis element `{}` occurs more than once", element_name),
+                ));
+            }
+            elements.push(element);
+        }
+        if !matches!(elements.len(), 1..=4 | 8) {
+            return Err(error(
+                *group_offset,
+                group,
+                format!("expected 1 to 4 or 8 basis elements per group, found {}", elements.len()),
+            ));
+        }
+        grouped_basis.push(elements);
+    }
+    Ok(MultiVectorClass {
+        class_name: class_name.to_string(),
+        doc,
+        grouped_basis,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error(descriptor: &str) -> ParseError {
+        match parse_descriptor(descriptor) {
+            Ok(_) => panic!("`{}` should not parse", descriptor),
+            Err(error) => error,
+        }
+    }
+
+    #[test]
+    fn generator_index_out_of_range() {
+        let error = parse_error("epga3d:1,1,1;Scalar:1;Vector:e0,e1,e4");
+        assert_eq!((error.offset, error.token.as_str()), (35, "e4"));
+        assert_eq!(error.message, "generator index 4 is out of range for an algebra with 3 generators");
+        assert_eq!(
+            error.to_string(),
+            "error: generator index 4 is out of range for an algebra with 3 generators\n  \
+             --> descriptor:1:36\n   \
+             | epga3d:1,1,1;Scalar:1;Vector:e0,e1,e4\n   \
+             |                                    ^^"
+        );
+    }
+
+    #[test]
+    fn missing_colon() {
+        let error = parse_error("epga3d:1,1,1;Scalar:1;Vector");
+        assert_eq!((error.offset, error.token.as_str()), (28, ""));
+        assert_eq!(
+            error.to_string(),
+            "error: expected `:` followed by a comma-separated list of basis elements\n  \
+             --> descriptor:1:29\n   \
+             | epga3d:1,1,1;Scalar:1;Vector\n   \
+             |                             ^"
+        );
+        let error = parse_error("epga3d");
+        assert_eq!((error.offset, error.token.as_str()), (6, ""));
+    }
+
+    #[test]
+    fn duplicate_element() {
+        let error = parse_error("epga3d:1,1,1;Scalar:1;Vector:e0,e1,e1");
+        assert_eq!((error.offset, error.token.as_str()), (35, "e1"));
+        assert_eq!(error.message, "basis element `e1` occurs more than once");
+        let error = parse_error("epga3d:1,1,1;Scalar:1;Line:e01,e02|e20");
+        assert_eq!((error.offset, error.token.as_str()), (35, "e20"));
+    }
+
+    #[test]
+    fn group_of_five() {
+        let error = parse_error("epga3d:1,1,1;Motor:1,e01,e02,e12,e012");
+        assert_eq!((error.offset, error.token.as_str()), (19, "1,e01,e02,e12,e012"));
+        assert_eq!(
+            error.to_string(),
+            "error: expected 1 to 4 or 8 basis elements per group, found 5\n  \
+             --> descriptor:1:20\n   \
+             | epga3d:1,1,1;Motor:1,e01,e02,e12,e012\n   \
+             |                    ^^^^^^^^^^^^^^^^^^"
+        );
+    }
+
+    #[test]
+    fn caret_on_later_line() {
+        let text = "name = \"epga3d\"\ngenerators = [\n    { name = \"e1\", square = 1 },\n    { name = \"e2\", square = 2 },\n]\n";
+        let error = match crate::config::parse_config("algebras/epga3d.toml", text) {
+            Ok(_) => panic!("`{}` should not parse", text),
+            Err(error) => error,
+        };
+        assert_eq!((error.offset, error.token.as_str()), (92, "2"));
+        assert_eq!(
+            error.to_string(),
+            "error: expected a generator square of `-1`, `0` or `1`\n  \
+             --> algebras/epga3d.toml:4:29\n   \
+             |     { name = \"e2\", square = 2 },\n   \
+             |                             ^"
+        );
+    }
+}
@@ -0,0 +1,502 @@
+/// Python code generation from AST
+///
+/// Emits a pure NumPy module: Every group of a class is an array whose last axis holds the elements of the group,
+/// while all leading axes are batch axes which are broadcast like in any other NumPy operation.
+/// Groups of a single element and scalars have no element axis.
+use crate::{
+    ast::{AstNode, DataType, Expression, ExpressionContent, Parameter, ScalarType},
+    emit::{camel_to_snake_case, emit_indentation},
+};
+
+/// Runtime support shared by all classes, emitted once at the top of the module
+const RUNTIME: &str = r#"
+
+def _splat(value, size):
+    """Repeats scalars along a new last axis"""
+    return np.repeat(np.asarray(value, dtype=DTYPE)[..., None], size, axis=-1)
+
+
+def _stack(*components):
+    """Stacks scalars of the same batch shape along a new last axis"""
+    return np.stack(np.broadcast_arrays(*components), axis=-1)
+
+
+_SCALAR_CLASS = None
+
+
+def _class_name(value):
+    """Name of the class of an argument, which selects the overload of a method"""
+    if isinstance(value, type):
+        return value.__name__
+    if isinstance(value, _MultiVectorClass):
+        return type(value).__name__
+    if isinstance(value, (int, np.integer)):
+        return "int"
+    return _SCALAR_CLASS
+
+
+def _register(cls, method_name, function, *argument_classes):
+    """Adds a generated function as an overload of a method, which is selected by the classes of the arguments"""
+    if method_name not in cls.__dict__:
+
+        def method(self, *arguments):
+            key = tuple(_class_name(argument) for argument in arguments)
+            if key not in method.overloads:
+                raise TypeError(f"{cls.__name__}.{method_name} is not implemented for ({', '.join(map(str, key))})")
+            return method.overloads[key](self, *arguments)
+
+        method.overloads = {}
+        setattr(cls, method_name, method)
+    cls.__dict__[method_name].overloads[argument_classes] = function
+
+
+class _MultiVectorClass:
+    """Base class of all multivector classes"""
+
+    group_sizes = ()
+
+    @classmethod
+    def from_elements(cls, elements):
+        """Splits an array with the elements of the class along the last axis into the groups"""
+        elements = np.asarray(elements, dtype=DTYPE)
+        groups = []
+        offset = 0
+        for size in cls.group_sizes:
+            groups.append(elements[..., offset] if size == 1 else elements[..., offset : offset + size])
+            offset += size
+        return cls(*groups)
+
+    @property
+    def groups(self):
+        return tuple(getattr(self, f"g{index}") for index in range(len(self.group_sizes)))
+
+    @property
+    def elements(self):
+        """Concatenates the groups into one array with the elements of the class along the last axis"""
+        groups = [group if size > 1 else group[..., None] for group, size in zip(self.groups, self.group_sizes)]
+        shape = np.broadcast_shapes(*(group.shape[:-1] for group in groups))
+        return np.concatenate([np.broadcast_to(group, shape + group.shape[-1:]) for group in groups], axis=-1)
+
+    def __repr__(self):
+        return f"{type(self).__name__}.from_elements({self.elements!r})"
+
+    def __neg__(self):
+        return self.neg()
+
+    def __add__(self, other):
+        return self.add(other)
+
+    def __sub__(self, other):
+        return self.sub(other)
+
+    def __mul__(self, other):
+        return self.mul(other)
+
+    def __truediv__(self, other):
+        return self.div(other)
+"#;
+
+/// Emits the NumPy representation of a scalar type
+fn emit_scalar_type<W: std::io::Write>(collector: &mut W, scalar_type: ScalarType) -> std::io::Result<()> {
+    match scalar_type {
+        ScalarType::F32 => collector.write_all(b"np.float32"),
+        ScalarType::F64 => collector.write_all(b"np.float64"),
+    }
+}
+
+/// Emits the name of the class of a parameter, as used to select overloads
+fn emit_class_name<W: std::io::Write>(collector: &mut W, data_type: &DataType) -> std::io::Result<()> {
+    match data_type {
+        DataType::Integer => collector.write_all(b"\"int\""),
+        DataType::MultiVector(class) => collector.write_fmt(format_args!("\"{}\"", class.class_name)),
+        DataType::SimdVector(_) => unreachable!(),
+    }
+}
+
+/// Emits the access of a component of a group
+fn emit_group_access<W: std::io::Write>(
+    collector: &mut W,
+    inner_expression: &Expression,
+    group_size: usize,
+    array_index: usize,
+    component_index: usize,
+) -> std::io::Result<()> {
+    emit_expression(collector, inner_expression)?;
+    if !inner_expression.is_scalar() {
+        collector.write_fmt(format_args!(".g{}", array_index))?;
+        if group_size > 1 {
+            collector.write_fmt(format_args!("[..., {}]", component_index))?;
+        }
+    }
+    Ok(())
+}
+
+/// Recursively emits Python code for an expression
+fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression) -> std::io::Result<()> {
+    match &expression.content {
+        ExpressionContent::None => unreachable!(),
+
+        // Variable reference
+        ExpressionContent::Variable(_data_type, name) => {
+            collector.write_all(name.as_bytes())?;
+        }
+
+        // Special case for scalar constructor calls
+        ExpressionContent::InvokeClassMethod(class, "Constructor", arguments) if class.is_scalar() => {
+            emit_expression(collector, &arguments[0].1)?;
+        }
+
+        // Method invocations (both class and instance methods)
+        ExpressionContent::InvokeClassMethod(_, _, arguments) | ExpressionContent::InvokeInstanceMethod(_, _, _, _, arguments) => {
+            match &expression.content {
+                // Instance method call
+                ExpressionContent::InvokeInstanceMethod(result_class, inner_expression, method_name, _, _) => {
+                    // Build function name from result class, argument classes, and method name
+                    if let DataType::MultiVector(result_class) = result_class {
+                        camel_to_snake_case(collector, &result_class.class_name)?;
+                        collector.write_all(b"_")?;
+                    }
+                    for (argument_class, _argument) in arguments.iter() {
+                        if let DataType::MultiVector(argument_class) = argument_class {
+                            camel_to_snake_case(collector, &argument_class.class_name)?;
+                            collector.write_all(b"_")?;
+                        }
+                    }
+                    camel_to_snake_case(collector, method_name)?;
+                    collector.write_all(b"(")?;
+                    emit_expression(collector, inner_expression)?;
+                    if !arguments.is_empty() {
+                        collector.write_all(b", ")?;
+                    }
+                }
+
+                // Class method call
+                ExpressionContent::InvokeClassMethod(class, method_name, _) => {
+                    if *method_name == "Constructor" {
+                        // Constructor call
+                        collector.write_all(class.class_name.as_bytes())?;
+                    } else {
+                        // Static method call
+                        camel_to_snake_case(collector, &class.class_name)?;
+                        collector.write_all(b"_")?;
+                        camel_to_snake_case(collector, method_name)?;
+                    }
+                    collector.write_all(b"(")?;
+                }
+                _ => unreachable!(),
+            }
+            for (i, (_argument_class, argument)) in arguments.iter().enumerate() {
+                if i > 0 {
+                    collector.write_all(b", ")?;
+                }
+                emit_expression(collector, argument)?;
+            }
+            collector.write_all(b")")?;
+        }
+
+        // Type conversion
+        ExpressionContent::Conversion(source_class, destination_class, inner_expression) => {
+            camel_to_snake_case(collector, &source_class.class_name)?;
+            collector.write_all(b"_")?;
+            camel_to_snake_case(collector, &destination_class.class_name)?;
+            collector.write_all(b"_into(")?;
+            emit_expression(collector, inner_expression)?;
+            collector.write_all(b")")?;
+        }
+
+        // Conditional expression
+        ExpressionContent::Select(condition_expression, then_expression, else_expression) => {
+            collector.write_all(b"(")?;
+            emit_expression(collector, then_expression)?;
+            collector.write_all(b" if ")?;
+            emit_expression(collector, condition_expression)?;
+            collector.write_all(b" else ")?;
+            emit_expression(collector, else_expression)?;
+            collector.write_all(b")")?;
+        }
+
+        // Group of a multivector
+        ExpressionContent::Access(inner_expression, array_index) => {
+            emit_expression(collector, inner_expression)?;
+            if !inner_expression.is_scalar() {
+                collector.write_fmt(format_args!(".g{}", array_index))?;
+            }
+        }
+
+        // Component reordering, which splats scalars
+        ExpressionContent::Swizzle(inner_expression, indices) => {
+            if inner_expression.size == 1 {
+                if expression.size > 1 {
+                    collector.write_all(b"_splat(")?;
+                    emit_expression(collector, inner_expression)?;
+                    collector.write_fmt(format_args!(", {})", expression.size))?;
+                } else {
+                    emit_expression(collector, inner_expression)?;
+                }
+            } else {
+                emit_expression(collector, inner_expression)?;
+                if indices.len() == 1 {
+                    collector.write_fmt(format_args!("[..., {}]", indices[0]))?;
+                } else {
+                    collector.write_fmt(format_args!(
+                        "[..., [{}]]",
+                        indices.iter().map(|index| index.to_string()).collect::<Vec<_>>().join(", ")
+                    ))?;
+                }
+            }
+        }
+
+        // Components of potentially different groups, a single index pair is splatted
+        ExpressionContent::Gather(inner_expression, indices) => {
+            if expression.size == 1 {
+                let (array_index, component_index) = indices[0];
+                emit_group_access(collector, inner_expression, inner_expression.size, array_index, component_index)?;
+            } else if indices.len() == 1 {
+                let (array_index, component_index) = indices[0];
+                collector.write_all(b"_splat(")?;
+                emit_group_access(collector, inner_expression, inner_expression.size, array_index, component_index)?;
+                collector.write_fmt(format_args!(", {})", expression.size))?;
+            } else {
+                collector.write_all(b"_stack(")?;
+                for (i, (array_index, component_index)) in indices.iter().enumerate() {
+                    if i > 0 {
+                        collector.write_all(b", ")?;
+                    }
+                    emit_group_access(collector, inner_expression, inner_expression.size, *array_index, *component_index)?;
+                }
+                collector.write_all(b")")?;
+            }
+        }
+
+        // Constant value, a single value is splatted
+        ExpressionContent::Constant(data_type, values) => match data_type {
+            DataType::Integer => collector.write_fmt(format_args!("{}", values[0]))?,
+            DataType::SimdVector(_size) => {
+                if expression.size == 1 {
+                    collector.write_fmt(format_args!("{:.1}", values[0] as f64))?
+                } else if values.len() == 1 {
+                    collector.write_fmt(format_args!("_splat({:.1}, {})", values[0] as f64, expression.size))?
+                } else {
+                    collector.write_fmt(format_args!(
+                        "np.array([{}], dtype=DTYPE)",
+                        values.iter().map(|value| format!("{:.1}", *value as f64)).collect::<Vec<_>>().join(", ")
+                    ))?
+                }
+            }
+            _ => unreachable!(),
+        },
+
+        // Mathematical function
+        ExpressionContent::SquareRoot(inner_expression) => {
+            collector.write_all(b"np.sqrt(")?;
+            emit_expression(collector, inner_expression)?;
+            collector.write_all(b")")?;
+        }
+
+        // Binary operations
+        // Fused multiply-add, for which NumPy has no ufunc
+        ExpressionContent::MultiplyAdd(a, b, c) => {
+            emit_expression(collector, c)?;
+            collector.write_all(b" + ")?;
+            emit_expression(collector, a)?;
+            collector.write_all(b" * ")?;
+            emit_expression(collector, b)?;
+        }
+
+        ExpressionContent::Add(lhs, rhs)
+        | ExpressionContent::Subtract(lhs, rhs)
+        | ExpressionContent::Multiply(lhs, rhs)
+        | ExpressionContent::Divide(lhs, rhs)
+        | ExpressionContent::LessThan(lhs, rhs)
+        | ExpressionContent::Equal(lhs, rhs)
+        | ExpressionContent::LogicAnd(lhs, rhs)
+        | ExpressionContent::BitShiftRight(lhs, rhs) => {
+            // Add parentheses for logical AND to ensure correct precedence
+            if let ExpressionContent::LogicAnd(_, _) = expression.content {
+                collector.write_all(b"(")?;
+            }
+            emit_expression(collector, lhs)?;
+            collector.write_all(match expression.content {
+                ExpressionContent::Add(_, _) => b" + ",
+                ExpressionContent::Subtract(_, _) => b" - ",
+                ExpressionContent::Multiply(_, _) => b" * ",
+                ExpressionContent::Divide(_, _) => b" / ",
+                ExpressionContent::LessThan(_, _) => b" < ",
+                ExpressionContent::Equal(_, _) => b" == ",
+                ExpressionContent::LogicAnd(_, _) => b" & ",
+                ExpressionContent::BitShiftRight(_, _) => b" >> ",
+                _ => unreachable!(),
+            })?;
+            emit_expression(collector, rhs)?;
+            if let ExpressionContent::LogicAnd(_, _) = expression.content {
+                collector.write_all(b")")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Emits the name of the function of a trait implementation, like in GLSL
+fn emit_function_name<W: std::io::Write>(collector: &mut W, result: &Parameter, parameters: &[Parameter]) -> std::io::Result<()> {
+    match parameters.len() {
+        0 => camel_to_snake_case(collector, &result.multi_vector_class().class_name)?,
+        1 if result.name == "Into" => {
+            camel_to_snake_case(collector, &parameters[0].multi_vector_class().class_name)?;
+            collector.write_all(b"_")?;
+            camel_to_snake_case(collector, &result.multi_vector_class().class_name)?;
+        }
+        1 => camel_to_snake_case(collector, &parameters[0].multi_vector_class().class_name)?,
+        2 if !matches!(parameters[1].data_type, DataType::MultiVector(_)) => {
+            camel_to_snake_case(collector, &parameters[0].multi_vector_class().class_name)?
+        }
+        2 => {
+            camel_to_snake_case(collector, &parameters[0].multi_vector_class().class_name)?;
+            collector.write_all(b"_")?;
+            camel_to_snake_case(collector, &parameters[1].multi_vector_class().class_name)?;
+        }
+        _ => unreachable!(),
+    }
+    collector.write_all(b"_")?;
+    camel_to_snake_case(collector, result.name)
+}
+
+/// Main function to emit Python code for an AST node
+pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, scalar_type: ScalarType, indentation: usize) -> std::io::Result<()> {
+    match ast_node {
+        AstNode::None => {}
+
+        // Imports and the runtime support for the classes
+        AstNode::Preamble { .. } => {
+            collector.write_all(b"import numpy as np\n\nDTYPE = ")?;
+            emit_scalar_type(collector, scalar_type)?;
+            collector.write_all(b"\n")?;
+            collector.write_all(RUNTIME.as_bytes())?;
+        }
+
+        // Class holding the groups as arrays
+        AstNode::ClassDefinition { class } => {
+            if class.is_scalar() {
+                // Scalars are plain arrays, but their class name is needed to select overloads
+                collector.write_fmt(format_args!("\n\n_SCALAR_CLASS = \"{}\"\n", class.class_name))?;
+                return Ok(());
+            }
+            collector.write_fmt(format_args!("\n\nclass {}(_MultiVectorClass):\n", class.class_name))?;
+            if let Some(doc) = &class.doc {
+                emit_indentation(collector, indentation + 1)?;
+                collector.write_fmt(format_args!("\"\"\"{}\"\"\"\n\n", doc.trim_end()))?;
+            }
+            emit_indentation(collector, indentation + 1)?;
+            let group_sizes = class.grouped_basis.iter().map(|group| group.len().to_string()).collect::<Vec<_>>();
+            collector.write_fmt(format_args!(
+                "group_sizes = ({}{})\n\n",
+                group_sizes.join(", "),
+                if group_sizes.len() == 1 { "," } else { "" }
+            ))?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_fmt(format_args!(
+                "def __init__(self, {}):\n",
+                (0..class.grouped_basis.len()).map(|i| format!("g{}", i)).collect::<Vec<_>>().join(", ")
+            ))?;
+            for (i, group) in class.grouped_basis.iter().enumerate() {
+                // Comment showing the basis elements in this group
+                emit_indentation(collector, indentation + 2)?;
+                collector.write_all(b"# ")?;
+                for (i, element) in group.iter().enumerate() {
+                    if i > 0 {
+                        collector.write_all(b", ")?;
+                    }
+                    collector.write_fmt(format_args!("{}", element))?;
+                }
+                collector.write_all(b"\n")?;
+                emit_indentation(collector, indentation + 2)?;
+                collector.write_fmt(format_args!("self.g{} = np.asarray(g{}, dtype=DTYPE)\n", i, i))?;
+            }
+        }
+
+        // Return statement
+        AstNode::ReturnStatement { expression } => {
+            collector.write_all(b"return ")?;
+            emit_expression(collector, expression)?;
+            collector.write_all(b"\n")?;
+        }
+
+        // Variable declaration/assignment
+        AstNode::VariableAssignment { name, expression, .. } => {
+            collector.write_fmt(format_args!("{} = ", name))?;
+            emit_expression(collector, expression)?;
+            collector.write_all(b"\n")?;
+        }
+
+        // If and while blocks
+        AstNode::IfThenBlock { condition, body } | AstNode::WhileLoopBlock { condition, body } => {
+            collector.write_all(match &ast_node {
+                AstNode::IfThenBlock { .. } => b"if ",
+                AstNode::WhileLoopBlock { .. } => b"while ",
+                _ => unreachable!(),
+            })?;
+            emit_expression(collector, condition)?;
+            collector.write_all(b":\n")?;
+            for statement in body.iter() {
+                emit_indentation(collector, indentation + 1)?;
+                emit_code(collector, statement, scalar_type, indentation + 1)?;
+            }
+        }
+
+        // Function definition, followed by its registration as a method of the class of the first parameter
+        AstNode::TraitImplementation { result, parameters, body } => {
+            collector.write_all(b"\n\ndef ")?;
+            emit_function_name(collector, result, parameters)?;
+            collector.write_fmt(format_args!(
+                "({}):\n",
+                parameters.iter().map(|parameter| parameter.name).collect::<Vec<_>>().join(", ")
+            ))?;
+            for statement in body.iter() {
+                emit_indentation(collector, indentation + 1)?;
+                emit_code(collector, statement, scalar_type, indentation + 1)?;
+            }
+            collector.write_all(b"\n\n")?;
+            match parameters.first() {
+                // Scalars are no classes, so these are only available as functions
+                None if result.multi_vector_class().is_scalar() => {}
+
+                // Constants become static methods, e.g. `Motor.one()`
+                None => {
+                    collector.write_fmt(format_args!("{}.", result.multi_vector_class().class_name))?;
+                    camel_to_snake_case(collector, result.name)?;
+                    collector.write_all(b" = staticmethod(")?;
+                    emit_function_name(collector, result, parameters)?;
+                    collector.write_all(b")\n")?;
+                }
+
+                // Scalars are no classes, so these are only available as functions
+                Some(parameter) if parameter.multi_vector_class().is_scalar() => {}
+
+                // Conversions take the destination class as argument, e.g. `motor.into(Rotor)`
+                Some(parameter) if result.name == "Into" => {
+                    collector.write_fmt(format_args!(
+                        "_register({}, \"into\", lambda self, _: ",
+                        parameter.multi_vector_class().class_name
+                    ))?;
+                    emit_function_name(collector, result, parameters)?;
+                    collector.write_all(b"(self), ")?;
+                    emit_class_name(collector, &result.data_type)?;
+                    collector.write_all(b")\n")?;
+                }
+
+                // Methods are selected by the classes of the other parameters, e.g. `motor.transformation(point)`
+                Some(parameter) => {
+                    collector.write_fmt(format_args!("_register({}, \"", parameter.multi_vector_class().class_name))?;
+                    camel_to_snake_case(collector, result.name)?;
+                    collector.write_all(b"\", ")?;
+                    emit_function_name(collector, result, parameters)?;
+                    for parameter in parameters[1..].iter() {
+                        collector.write_all(b", ")?;
+                        emit_class_name(collector, &parameter.data_type)?;
+                    }
+                    collector.write_all(b")\n")?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
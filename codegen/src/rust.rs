@@ -1,22 +1,33 @@
 /// Code generator for Rust target language
 use crate::{
-    ast::{AstNode, DataType, Expression, ExpressionContent, Parameter},
+    ast::{AstNode, DataType, Expression, ExpressionContent, Parameter, ScalarType},
     emit::{camel_to_snake_case, emit_element_name, emit_indentation},
 };
 
+/// Emit ast::ScalarType to Rust primitive type
+fn emit_scalar_type<W: std::io::Write>(collector: &mut W, scalar_type: ScalarType) -> std::io::Result<()> {
+    match scalar_type {
+        ScalarType::F32 => collector.write_all(b"f32"),
+        ScalarType::F64 => collector.write_all(b"f64"),
+    }
+}
+
 /// Emit ast::DataType to Rust type
-fn emit_data_type<W: std::io::Write>(collector: &mut W, data_type: &DataType) -> std::io::Result<()> {
+fn emit_data_type<W: std::io::Write>(collector: &mut W, data_type: &DataType, scalar_type: ScalarType) -> std::io::Result<()> {
     match data_type {
-        DataType::Integer => collector.write_all(b"isize"),                      // Integers map to isize
-        DataType::SimdVector(size) if *size == 1 => collector.write_all(b"f32"), // Size-1 vectors as f32
-        DataType::SimdVector(size) => collector.write_fmt(format_args!("Simd32x{}", *size)), // Vectors to SIMD
-        DataType::MultiVector(class) if class.is_scalar() => collector.write_all(b"f32"), // Scalar MV as f32
-        DataType::MultiVector(class) => collector.write_fmt(format_args!("{}", class.class_name)), // MV to class
+        DataType::Integer => collector.write_all(b"isize"), // Integers map to isize
+        DataType::SimdVector(size) if *size == 1 => emit_scalar_type(collector, scalar_type), // Size-1 vectors as scalar
+        DataType::SimdVector(size) => match scalar_type {
+            ScalarType::F32 => collector.write_fmt(format_args!("Simd32x{}", *size)), // Vectors to SIMD
+            ScalarType::F64 => collector.write_fmt(format_args!("Simd64x{}", *size)),
+        },
+        DataType::MultiVector(class) if class.is_scalar() => emit_scalar_type(collector, scalar_type), // Scalar MV as scalar
+        DataType::MultiVector(class) => collector.write_fmt(format_args!("{}", class.class_name)),     // MV to class
     }
 }
 
 /// Emit ast::Expression to Rust code
-fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression) -> std::io::Result<()> {
+fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression, scalar_type: ScalarType) -> std::io::Result<()> {
     match &expression.content {
         ExpressionContent::None => unreachable!(),
 
@@ -27,7 +38,7 @@ fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression
 
         // Method call on an object
         ExpressionContent::InvokeInstanceMethod(_result_class, inner_expression, method_name, _, arguments) => {
-            emit_expression(collector, inner_expression)?;
+            emit_expression(collector, inner_expression, scalar_type)?;
             collector.write_all(b".")?;
             camel_to_snake_case(collector, method_name)?; // Convert method name to snake_case
             collector.write_all(b"(")?;
@@ -35,14 +46,14 @@ fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression
                 if i > 0 {
                     collector.write_all(b", ")?;
                 }
-                emit_expression(collector, argument)?;
+                emit_expression(collector, argument, scalar_type)?;
             }
             collector.write_all(b")")?;
         }
 
         // Special case for scalar constructor - just pass through the argument
         ExpressionContent::InvokeClassMethod(class, "Constructor", arguments) if class.is_scalar() => {
-            emit_expression(collector, &arguments[0].1)?;
+            emit_expression(collector, &arguments[0].1, scalar_type)?;
         }
 
         // Object constructor with explicit initialization of group fields
@@ -53,14 +64,14 @@ fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression
                     collector.write_all(b", ")?;
                 }
                 collector.write_fmt(format_args!("g{}: ", i))?;
-                emit_expression(collector, argument)?;
+                emit_expression(collector, argument, scalar_type)?;
             }
             collector.write_all(b" } }")?;
         }
 
         // Static class method call
         ExpressionContent::InvokeClassMethod(class, method_name, arguments) => {
-            emit_data_type(collector, &DataType::MultiVector(class))?;
+            emit_data_type(collector, &DataType::MultiVector(class), scalar_type)?;
             collector.write_all(b"::")?;
             camel_to_snake_case(collector, method_name)?;
             collector.write_all(b"(")?;
@@ -68,31 +79,31 @@ fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression
                 if i > 0 {
                     collector.write_all(b", ")?;
                 }
-                emit_expression(collector, argument)?;
+                emit_expression(collector, argument, scalar_type)?;
             }
             collector.write_all(b")")?;
         }
 
         // Type conversion using Rust's into() method
         ExpressionContent::Conversion(_source_class, _destination_class, inner_expression) => {
-            emit_expression(collector, inner_expression)?;
+            emit_expression(collector, inner_expression, scalar_type)?;
             collector.write_all(b".into()")?;
         }
 
         // Conditional expression using Rust's if/else blocks
         ExpressionContent::Select(condition_expression, then_expression, else_expression) => {
             collector.write_all(b"if ")?;
-            emit_expression(collector, condition_expression)?;
+            emit_expression(collector, condition_expression, scalar_type)?;
             collector.write_all(b" { ")?;
-            emit_expression(collector, then_expression)?;
+            emit_expression(collector, then_expression, scalar_type)?;
             collector.write_all(b" } else { ")?;
-            emit_expression(collector, else_expression)?;
+            emit_expression(collector, else_expression, scalar_type)?;
             collector.write_all(b" }")?;
         }
 
         // Array/vector element access
         ExpressionContent::Access(inner_expression, array_index) => {
-            emit_expression(collector, inner_expression)?;
+            emit_expression(collector, inner_expression, scalar_type)?;
             if !inner_expression.is_scalar() {
                 collector.write_fmt(format_args!(".group{}()", array_index))?;
             }
@@ -101,13 +112,17 @@ fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression
         // SIMD vector component swizzling
         ExpressionContent::Swizzle(inner_expression, indices) => {
             if expression.size == 1 {
-                emit_expression(collector, inner_expression)?;
+                emit_expression(collector, inner_expression, scalar_type)?;
                 if inner_expression.size > 1 {
                     collector.write_fmt(format_args!("[{}]", indices[0]))?;
                 }
             } else {
-                collector.write_all(b"swizzle!(")?; // Using a macro for component reordering
-                emit_expression(collector, inner_expression)?;
+                // Using a macro for component reordering
+                collector.write_all(match scalar_type {
+                    ScalarType::F32 => b"swizzle!(",
+                    ScalarType::F64 => b"swizzle64!(",
+                })?;
+                emit_expression(collector, inner_expression, scalar_type)?;
                 collector.write_all(b", ")?;
                 for (i, component_index) in indices.iter().enumerate() {
                     if i > 0 {
@@ -123,10 +138,10 @@ fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression
         ExpressionContent::Gather(inner_expression, indices) => {
             // Different handling based on the expression complexity
             if expression.size == 1 && inner_expression.is_scalar() {
-                emit_expression(collector, inner_expression)?;
+                emit_expression(collector, inner_expression, scalar_type)?;
             } else {
                 if expression.size > 1 {
-                    emit_data_type(collector, &DataType::SimdVector(expression.size))?;
+                    emit_data_type(collector, &DataType::SimdVector(expression.size), scalar_type)?;
                     collector.write_all(b"::from(")?;
                 }
                 if indices.len() > 1 {
@@ -136,7 +151,7 @@ fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression
                     if i > 0 {
                         collector.write_all(b", ")?;
                     }
-                    emit_expression(collector, inner_expression)?;
+                    emit_expression(collector, inner_expression, scalar_type)?;
                     if !inner_expression.is_scalar() {
                         collector.write_fmt(format_args!(".group{}()", array_index))?;
                         if inner_expression.size > 1 {
@@ -160,7 +175,7 @@ fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression
                 if expression.size == 1 {
                     collector.write_fmt(format_args!("{:.1}", values[0] as f32))?;
                 } else {
-                    emit_data_type(collector, &DataType::SimdVector(expression.size))?;
+                    emit_data_type(collector, &DataType::SimdVector(expression.size), scalar_type)?;
                     collector.write_all(b"::from(")?;
                     if values.len() > 1 {
                         collector.write_all(b"[")?;
@@ -182,11 +197,41 @@ fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression
 
         // Square root function as a method call
         ExpressionContent::SquareRoot(inner_expression) => {
-            emit_expression(collector, inner_expression)?;
-            collector.write_all(b".sqrt()")?;
+            // Method calls bind stronger than the binary operators
+            let is_binary_operation = matches!(
+                inner_expression.content,
+                ExpressionContent::Add(_, _)
+                    | ExpressionContent::Subtract(_, _)
+                    | ExpressionContent::Multiply(_, _)
+                    | ExpressionContent::Divide(_, _)
+            );
+            if is_binary_operation {
+                collector.write_all(b"(")?;
+            }
+            emit_expression(collector, inner_expression, scalar_type)?;
+            collector.write_all(if is_binary_operation { b").sqrt()" } else { b".sqrt()" })?;
         }
 
         // Binary operations
+        // Fused multiply-add of SIMD vectors, scalars are not fused as `f32::mul_add` is slow without hardware support
+        ExpressionContent::MultiplyAdd(a, b, c) if expression.size > 1 => {
+            emit_data_type(collector, &DataType::SimdVector(expression.size), scalar_type)?;
+            collector.write_all(b"::mul_add(")?;
+            emit_expression(collector, a, scalar_type)?;
+            collector.write_all(b", ")?;
+            emit_expression(collector, b, scalar_type)?;
+            collector.write_all(b", ")?;
+            emit_expression(collector, c, scalar_type)?;
+            collector.write_all(b")")?;
+        }
+        ExpressionContent::MultiplyAdd(a, b, c) => {
+            emit_expression(collector, c, scalar_type)?;
+            collector.write_all(b" + ")?;
+            emit_expression(collector, a, scalar_type)?;
+            collector.write_all(b" * ")?;
+            emit_expression(collector, b, scalar_type)?;
+        }
+
         ExpressionContent::Add(lhs, rhs)
         | ExpressionContent::Subtract(lhs, rhs)
         | ExpressionContent::Multiply(lhs, rhs)
@@ -195,7 +240,7 @@ fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression
         | ExpressionContent::Equal(lhs, rhs)
         | ExpressionContent::LogicAnd(lhs, rhs)
         | ExpressionContent::BitShiftRight(lhs, rhs) => {
-            emit_expression(collector, lhs)?;
+            emit_expression(collector, lhs, scalar_type)?;
             collector.write_all(match expression.content {
                 ExpressionContent::Add(_, _) => b" + ",
                 ExpressionContent::Subtract(_, _) => b" - ",
@@ -207,14 +252,19 @@ fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression
                 ExpressionContent::BitShiftRight(_, _) => b" >> ",
                 _ => unreachable!(),
             })?;
-            emit_expression(collector, rhs)?;
+            emit_expression(collector, rhs, scalar_type)?;
         }
     }
     Ok(())
 }
 
 /// Emit OpAssign (eg AddAssign, MulAssign etc) trait implementations for algebraic operations
-fn emit_assign_trait<W: std::io::Write>(collector: &mut W, result: &Parameter, parameters: &[Parameter]) -> std::io::Result<()> {
+fn emit_assign_trait<W: std::io::Write>(
+    collector: &mut W,
+    result: &Parameter,
+    parameters: &[Parameter],
+    scalar_type: ScalarType,
+) -> std::io::Result<()> {
     // Skip if operating on different types
     if result.multi_vector_class() != parameters[0].multi_vector_class() {
         return Ok(());
@@ -222,29 +272,52 @@ fn emit_assign_trait<W: std::io::Write>(collector: &mut W, result: &Parameter, p
 
     // Generate assignment trait implementation (e.g., AddAssign, MulAssign)
     collector.write_fmt(format_args!("impl {}Assign<", result.name))?;
-    emit_data_type(collector, &parameters[1].data_type)?;
+    emit_data_type(collector, &parameters[1].data_type, scalar_type)?;
     collector.write_all(b"> for ")?;
-    emit_data_type(collector, &parameters[0].data_type)?;
+    emit_data_type(collector, &parameters[0].data_type, scalar_type)?;
     collector.write_all(b" {\n    fn ")?;
     camel_to_snake_case(collector, result.name)?;
     collector.write_all(b"_assign(&mut self, other: ")?;
-    emit_data_type(collector, &parameters[1].data_type)?;
+    emit_data_type(collector, &parameters[1].data_type, scalar_type)?;
     collector.write_all(b") {\n        *self = (*self).")?;
     camel_to_snake_case(collector, result.name)?;
     collector.write_all(b"(other);\n    }\n}\n\n")
 }
 
+/// Checks if a variable is assigned again after its declaration, in which case it has to be mutable
+fn is_reassigned(body: &[AstNode], variable_name: &str) -> bool {
+    body.iter().any(|statement| match statement {
+        AstNode::VariableAssignment { name, data_type: None, .. } => *name == variable_name,
+        AstNode::IfThenBlock { body, .. } | AstNode::WhileLoopBlock { body, .. } => is_reassigned(body, variable_name),
+        _ => false,
+    })
+}
+
 /// Main code generation function for emitting ast nodes to rust code
-pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, indentation: usize) -> std::io::Result<()> {
+pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, scalar_type: ScalarType, indentation: usize) -> std::io::Result<()> {
+    emit_statement(collector, ast_node, scalar_type, indentation, true)
+}
+
+/// Emits an ast node, with `is_mutable` telling if variables declared by it are assigned again
+fn emit_statement<W: std::io::Write>(
+    collector: &mut W,
+    ast_node: &AstNode,
+    scalar_type: ScalarType,
+    indentation: usize,
+    is_mutable: bool,
+) -> std::io::Result<()> {
     match &ast_node {
         // Empty node
         AstNode::None => {}
 
         // Code preamble - imports and directives
-        AstNode::Preamble => {
-            collector.write_all(b"#![allow(clippy::assign_op_pattern)]\n")?;
-            collector
-                .write_all(b"use crate::{simd::*, *};\nuse std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};\n\n")?;
+        AstNode::Preamble { crate_path } => {
+            // Inner attributes are not allowed in files which downstream crates `include!`
+            if *crate_path == "crate" {
+                collector.write_all(b"#![allow(clippy::assign_op_pattern, clippy::from_over_into)]\n")?;
+            }
+            collector.write_fmt(format_args!("use {}::{{simd::*, *}};\n", crate_path))?;
+            collector.write_all(b"use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};\n\n")?;
         }
 
         // Class definition - generates struct definition and implementations
@@ -254,13 +327,20 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
                 return Ok(());
             }
 
-            // Calculate total element count and SIMD widths
+            // Calculate total element count and the memory layout, with `None` for the padding
             let element_count = class.grouped_basis.iter().fold(0, |a, b| a + b.len());
-            let mut simd_widths = Vec::new();
+            let (group_padding, trailing_padding) = class.padding();
+            let mut layout = Vec::new();
+            for (group, (before, after)) in class.grouped_basis.iter().zip(group_padding.iter()) {
+                layout.resize(layout.len() + *before, None);
+                layout.extend(group.iter().map(Some));
+                layout.resize(layout.len() + *after, None);
+            }
+            layout.resize(layout.len() + trailing_padding, None);
 
             // Generate inner Groups struct for SIMD vector storage
             emit_indentation(collector, indentation)?;
-            collector.write_fmt(format_args!("#[derive(Clone, Copy)]\nstruct {}Groups {{\n", class.class_name))?;
+            collector.write_fmt(format_args!("#[derive(Clone, Copy)]\n#[repr(C)]\nstruct {}Groups {{\n", class.class_name))?;
             for (j, group) in class.grouped_basis.iter().enumerate() {
                 // Generate field documentation with basis elements
                 emit_indentation(collector, indentation + 1)?;
@@ -276,40 +356,42 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
                 // Generate field with appropriate SIMD type
                 emit_indentation(collector, indentation + 1)?;
                 collector.write_fmt(format_args!("g{}: ", j))?;
-                emit_data_type(collector, &DataType::SimdVector(group.len()))?;
+                emit_data_type(collector, &DataType::SimdVector(group.len()), scalar_type)?;
                 collector.write_all(b",\n")?;
-
-                // Track SIMD width for memory layout
-                simd_widths.push(if group.len() == 1 { 1 } else { 4 });
             }
             collector.write_all(b"}\n\n")?;
 
             // Generate main class as a union of groups and raw elements
+            if let Some(doc) = &class.doc {
+                for line in doc.lines() {
+                    emit_indentation(collector, indentation)?;
+                    collector.write_fmt(format_args!("///{}{}\n", if line.is_empty() { "" } else { " " }, line))?;
+                }
+            }
             emit_indentation(collector, indentation)?;
-            collector.write_fmt(format_args!("#[derive(Clone, Copy)]\npub union {} {{\n", class.class_name))?;
+            collector.write_fmt(format_args!("#[derive(Clone, Copy)]\n#[repr(C)]\npub union {} {{\n", class.class_name))?;
             emit_indentation(collector, indentation + 1)?;
             collector.write_fmt(format_args!("groups: {}Groups,\n", class.class_name))?;
 
             // Add documentation showing all elements
             emit_indentation(collector, indentation + 1)?;
             collector.write_all(b"/// ")?;
-            for (j, group) in class.grouped_basis.iter().enumerate() {
-                for (i, element) in group.iter().enumerate() {
-                    if j > 0 || i > 0 {
-                        collector.write_all(b", ")?;
-                    }
-                    collector.write_fmt(format_args!("{}", element))?;
+            for (i, element) in layout.iter().enumerate() {
+                if i > 0 {
+                    collector.write_all(b", ")?;
                 }
-                // Add padding zeros for alignment
-                for _ in group.len()..simd_widths[j] {
-                    collector.write_all(b", 0")?;
+                match element {
+                    Some(element) => collector.write_fmt(format_args!("{}", element))?,
+                    None => collector.write_all(b"0")?, // Padding zeros for alignment
                 }
             }
             collector.write_all(b"\n")?;
 
             // Raw elements array field
             emit_indentation(collector, indentation + 1)?;
-            collector.write_fmt(format_args!("elements: [f32; {}],\n", simd_widths.iter().fold(0, |a, b| a + b)))?;
+            collector.write_all(b"elements: [")?;
+            emit_scalar_type(collector, scalar_type)?;
+            collector.write_fmt(format_args!("; {}],\n", layout.len()))?;
             emit_indentation(collector, indentation)?;
             collector.write_all(b"}\n\n")?;
 
@@ -329,7 +411,8 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
                         collector.write_all(b", ")?;
                     }
                     emit_element_name(collector, element)?;
-                    collector.write_all(b": f32")?;
+                    collector.write_all(b": ")?;
+                    emit_scalar_type(collector, scalar_type)?;
                     element_index += 1;
                 }
             }
@@ -338,18 +421,13 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
             // Constructor body
             emit_indentation(collector, indentation + 2)?;
             collector.write_all(b"Self { elements: [")?;
-            element_index = 0;
-            for (j, group) in class.grouped_basis.iter().enumerate() {
-                for element in group.iter() {
-                    if element_index > 0 {
-                        collector.write_all(b", ")?;
-                    }
-                    emit_element_name(collector, element)?;
-                    element_index += 1;
+            for (i, element) in layout.iter().enumerate() {
+                if i > 0 {
+                    collector.write_all(b", ")?;
                 }
-                // Add padding zeros
-                for _ in group.len()..simd_widths[j] {
-                    collector.write_all(b", 0.0")?;
+                match element {
+                    Some(element) => emit_element_name(collector, element)?,
+                    None => collector.write_all(b"0.0")?, // Padding zeros
                 }
             }
             collector.write_all(b"] }\n")?;
@@ -357,6 +435,10 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
             collector.write_all(b"}\n")?;
 
             // Generate from_groups constructor
+            if class.grouped_basis.len() > 7 {
+                emit_indentation(collector, indentation + 1)?;
+                collector.write_all(b"#[allow(clippy::too_many_arguments)]\n")?;
+            }
             emit_indentation(collector, indentation + 1)?;
             collector.write_all(b"pub const fn from_groups(")?;
             for (j, group) in class.grouped_basis.iter().enumerate() {
@@ -364,7 +446,7 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
                     collector.write_all(b", ")?;
                 }
                 collector.write_fmt(format_args!("g{}: ", j))?;
-                emit_data_type(collector, &DataType::SimdVector(group.len()))?;
+                emit_data_type(collector, &DataType::SimdVector(group.len()), scalar_type)?;
             }
             collector.write_all(b") -> Self {\n")?;
             emit_indentation(collector, indentation + 2)?;
@@ -386,7 +468,7 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
                 collector.write_all(b"#[inline(always)]\n")?;
                 emit_indentation(collector, indentation + 1)?;
                 collector.write_fmt(format_args!("pub fn group{}(&self) -> ", j))?;
-                emit_data_type(collector, &DataType::SimdVector(group.len()))?;
+                emit_data_type(collector, &DataType::SimdVector(group.len()), scalar_type)?;
                 collector.write_all(b" {\n")?;
                 emit_indentation(collector, indentation + 2)?;
                 collector.write_fmt(format_args!("unsafe {{ self.groups.g{} }}\n", j))?;
@@ -398,7 +480,7 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
                 collector.write_all(b"#[inline(always)]\n")?;
                 emit_indentation(collector, indentation + 1)?;
                 collector.write_fmt(format_args!("pub fn group{}_mut(&mut self) -> &mut ", j))?;
-                emit_data_type(collector, &DataType::SimdVector(group.len()))?;
+                emit_data_type(collector, &DataType::SimdVector(group.len()), scalar_type)?;
                 collector.write_all(b" {\n")?;
                 emit_indentation(collector, indentation + 2)?;
                 collector.write_fmt(format_args!("unsafe {{ &mut self.groups.g{} }}\n", j))?;
@@ -415,26 +497,27 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
                 class.class_name.to_uppercase(),
                 element_count
             ))?;
-            let mut element_index = 0;
-            let mut index_remap = Vec::new();
-            for (j, group) in class.grouped_basis.iter().enumerate() {
-                for _ in 0..group.len() {
-                    if element_index > 0 {
-                        collector.write_all(b", ")?;
-                    }
-                    collector.write_fmt(format_args!("{}", element_index))?;
-                    index_remap.push(element_index);
-                    element_index += 1;
+            let index_remap: Vec<usize> = layout
+                .iter()
+                .enumerate()
+                .filter(|(_, element)| element.is_some())
+                .map(|(i, _)| i)
+                .collect();
+            for (i, remapped) in index_remap.iter().enumerate() {
+                if i > 0 {
+                    collector.write_all(b", ")?;
                 }
-                element_index += simd_widths[j].saturating_sub(group.len());
+                collector.write_fmt(format_args!("{}", remapped))?;
             }
             collector.write_all(b"];\n\n")?;
 
             // Implement Index trait
             emit_indentation(collector, indentation)?;
-            collector.write_fmt(format_args!("impl std::ops::Index<usize> for {} {{\n", class.class_name))?;
+            collector.write_fmt(format_args!("impl core::ops::Index<usize> for {} {{\n", class.class_name))?;
             emit_indentation(collector, indentation + 1)?;
-            collector.write_all(b"type Output = f32;\n\n")?;
+            collector.write_all(b"type Output = ")?;
+            emit_scalar_type(collector, scalar_type)?;
+            collector.write_all(b";\n\n")?;
             emit_indentation(collector, indentation + 1)?;
             collector.write_all(b"fn index(&self, index: usize) -> &Self::Output {\n")?;
             emit_indentation(collector, indentation + 2)?;
@@ -449,7 +532,7 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
 
             // Implement IndexMut trait
             emit_indentation(collector, indentation)?;
-            collector.write_fmt(format_args!("impl std::ops::IndexMut<usize> for {} {{\n", class.class_name))?;
+            collector.write_fmt(format_args!("impl core::ops::IndexMut<usize> for {} {{\n", class.class_name))?;
             emit_indentation(collector, indentation + 1)?;
             collector.write_all(b"fn index_mut(&mut self, index: usize) -> &mut Self::Output {\n")?;
             emit_indentation(collector, indentation + 2)?;
@@ -464,10 +547,9 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
 
             // Implement conversion to array
             emit_indentation(collector, indentation)?;
-            collector.write_fmt(format_args!(
-                "impl std::convert::From<{}> for [f32; {}] {{\n",
-                class.class_name, element_count
-            ))?;
+            collector.write_fmt(format_args!("impl core::convert::From<{}> for [", class.class_name))?;
+            emit_scalar_type(collector, scalar_type)?;
+            collector.write_fmt(format_args!("; {}] {{\n", element_count))?;
             emit_indentation(collector, indentation + 1)?;
             collector.write_fmt(format_args!("fn from(vector: {}) -> Self {{\n", class.class_name))?;
             emit_indentation(collector, indentation + 2)?;
@@ -486,26 +568,25 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
 
             // Implement conversion from array
             emit_indentation(collector, indentation)?;
-            collector.write_fmt(format_args!(
-                "impl std::convert::From<[f32; {}]> for {} {{\n",
-                element_count, class.class_name
-            ))?;
+            collector.write_all(b"impl core::convert::From<[")?;
+            emit_scalar_type(collector, scalar_type)?;
+            collector.write_fmt(format_args!("; {}]> for {} {{\n", element_count, class.class_name))?;
             emit_indentation(collector, indentation + 1)?;
-            collector.write_fmt(format_args!("fn from(array: [f32; {}]) -> Self {{\n", element_count))?;
+            collector.write_all(b"fn from(array: [")?;
+            emit_scalar_type(collector, scalar_type)?;
+            collector.write_fmt(format_args!("; {}]) -> Self {{\n", element_count))?;
             emit_indentation(collector, indentation + 2)?;
             collector.write_all(b"Self { elements: [")?;
             let mut element_index = 0;
-            for (j, group) in class.grouped_basis.iter().enumerate() {
-                for _ in 0..group.len() {
-                    if element_index > 0 {
-                        collector.write_all(b", ")?;
-                    }
+            for (i, element) in layout.iter().enumerate() {
+                if i > 0 {
+                    collector.write_all(b", ")?;
+                }
+                if element.is_some() {
                     collector.write_fmt(format_args!("array[{}]", element_index))?;
                     element_index += 1;
-                }
-                // Add padding zeros
-                for _ in group.len()..simd_widths[j] {
-                    collector.write_all(b", 0.0")?;
+                } else {
+                    collector.write_all(b"0.0")?; // Padding zeros
                 }
             }
             collector.write_all(b"] }\n")?;
@@ -516,9 +597,9 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
 
             // Implement Debug trait for nicer formatting
             emit_indentation(collector, indentation)?;
-            collector.write_fmt(format_args!("impl std::fmt::Debug for {} {{\n", class.class_name))?;
+            collector.write_fmt(format_args!("impl core::fmt::Debug for {} {{\n", class.class_name))?;
             emit_indentation(collector, indentation + 1)?;
-            collector.write_all(b"fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {\n")?;
+            collector.write_all(b"fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {\n")?;
             emit_indentation(collector, indentation + 2)?;
             collector.write_all(b"formatter\n")?;
             emit_indentation(collector, indentation + 3)?;
@@ -537,26 +618,93 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
             collector.write_all(b"}\n")?;
             emit_indentation(collector, indentation)?;
             collector.write_all(b"}\n\n")?;
+
+            // Implement serde traits as a map of the basis element names or as an array of the elements
+            // (`cfg_serde!` and `cfg_bytemuck!` check the features of the `geometric_algebra` crate, even in code generated for a downstream crate)
+            emit_indentation(collector, indentation)?;
+            collector.write_all(b"cfg_serde! {\n")?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_fmt(format_args!(
+                "const {}_ELEMENT_NAMES: [&str; {}] = [",
+                class.class_name.to_uppercase(),
+                element_count
+            ))?;
+            for (i, element) in class.grouped_basis.iter().flatten().enumerate() {
+                if i > 0 {
+                    collector.write_all(b", ")?;
+                }
+                collector.write_fmt(format_args!("\"{}\"", element))?;
+            }
+            collector.write_all(b"];\n\n")?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_fmt(format_args!("impl serialization::serde::Serialize for {} {{\n", class.class_name))?;
+            emit_indentation(collector, indentation + 2)?;
+            collector.write_all(b"fn serialize<S: serialization::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {\n")?;
+            emit_indentation(collector, indentation + 3)?;
+            collector.write_all(b"serialization::serialize_elements(&<[")?;
+            emit_scalar_type(collector, scalar_type)?;
+            collector.write_fmt(format_args!(
+                "; {}]>::from(*self), &{}_ELEMENT_NAMES, serializer)\n",
+                element_count,
+                class.class_name.to_uppercase()
+            ))?;
+            emit_indentation(collector, indentation + 2)?;
+            collector.write_all(b"}\n")?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_all(b"}\n\n")?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_fmt(format_args!(
+                "impl<'de> serialization::serde::Deserialize<'de> for {} {{\n",
+                class.class_name
+            ))?;
+            emit_indentation(collector, indentation + 2)?;
+            collector.write_all(b"fn deserialize<D: serialization::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {\n")?;
+            emit_indentation(collector, indentation + 3)?;
+            collector.write_fmt(format_args!(
+                "serialization::deserialize_elements(&{}_ELEMENT_NAMES, deserializer).map(Self::from)\n",
+                class.class_name.to_uppercase()
+            ))?;
+            emit_indentation(collector, indentation + 2)?;
+            collector.write_all(b"}\n")?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_all(b"}\n")?;
+            emit_indentation(collector, indentation)?;
+            collector.write_all(b"}\n\n")?;
+
+            // Implement bytemuck traits, Pod only if there are no implicit padding bytes which would be uninitialized
+            // (the unused lanes of `Simd32x3` and the like are always initialized, see `simd.rs`)
+            emit_indentation(collector, indentation)?;
+            collector.write_all(b"cfg_bytemuck! {\n")?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_fmt(format_args!("unsafe impl bytemuck::Zeroable for {} {{}}\n", class.class_name))?;
+            let (group_padding, trailing_padding) = class.padding();
+            if group_padding.iter().all(|(before, _after)| *before == 0) && trailing_padding == 0 {
+                collector.write_all(b"\n")?;
+                emit_indentation(collector, indentation + 1)?;
+                collector.write_fmt(format_args!("unsafe impl bytemuck::Pod for {} {{}}\n", class.class_name))?;
+            }
+            emit_indentation(collector, indentation)?;
+            collector.write_all(b"}\n\n")?;
         }
 
         // Return statement
         AstNode::ReturnStatement { expression } => {
             collector.write_all(b"return ")?;
-            emit_expression(collector, expression)?;
+            emit_expression(collector, expression, scalar_type)?;
             collector.write_all(b";\n")?;
         }
 
         // Variable assignment
         AstNode::VariableAssignment { name, data_type, expression } => {
             if let Some(data_type) = data_type {
-                collector.write_fmt(format_args!("let mut {}", name))?;
+                collector.write_fmt(format_args!("let {}{}", if is_mutable { "mut " } else { "" }, name))?;
                 collector.write_all(b": ")?;
-                emit_data_type(collector, data_type)?;
+                emit_data_type(collector, data_type, scalar_type)?;
             } else {
                 collector.write_fmt(format_args!("{}", name))?;
             }
             collector.write_all(b" = ")?;
-            emit_expression(collector, expression)?;
+            emit_expression(collector, expression, scalar_type)?;
             collector.write_all(b";\n")?;
         }
 
@@ -567,11 +715,11 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
                 AstNode::WhileLoopBlock { .. } => b"while ",
                 _ => unreachable!(),
             })?;
-            emit_expression(collector, condition)?;
+            emit_expression(collector, condition, scalar_type)?;
             collector.write_all(b" {\n")?;
             for statement in body.iter() {
                 emit_indentation(collector, indentation + 1)?;
-                emit_code(collector, statement, indentation + 1)?;
+                emit_code(collector, statement, scalar_type, indentation + 1)?;
             }
             emit_indentation(collector, indentation)?;
             collector.write_all(b"}\n")?;
@@ -596,7 +744,7 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
                 0 => &result.data_type,
                 1 if result.name == "Into" => {
                     collector.write_all(b"<")?;
-                    emit_data_type(collector, &result.data_type)?;
+                    emit_data_type(collector, &result.data_type, scalar_type)?;
                     collector.write_all(b">")?;
                     &parameters[0].data_type
                 }
@@ -604,21 +752,21 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
                 2 if !matches!(parameters[1].data_type, DataType::MultiVector(_)) => &parameters[0].data_type,
                 2 => {
                     collector.write_all(b"<")?;
-                    emit_data_type(collector, &parameters[1].data_type)?;
+                    emit_data_type(collector, &parameters[1].data_type, scalar_type)?;
                     collector.write_all(b">")?;
                     &parameters[0].data_type
                 }
                 _ => unreachable!(),
             };
             collector.write_all(b" for ")?;
-            emit_data_type(collector, impl_for)?;
+            emit_data_type(collector, impl_for, scalar_type)?;
             collector.write_all(b" {\n")?;
 
             // Add associated Output type for operators
             if !parameters.is_empty() && result.name != "Into" {
                 emit_indentation(collector, indentation + 1)?;
                 collector.write_all(b"type Output = ")?;
-                emit_data_type(collector, &result.data_type)?;
+                emit_data_type(collector, &result.data_type, scalar_type)?;
                 collector.write_all(b";\n\n")?;
             }
 
@@ -630,13 +778,13 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
                 0 => collector.write_all(b"() -> Self")?,
                 1 => {
                     collector.write_fmt(format_args!("({}) -> ", parameters[0].name))?;
-                    emit_data_type(collector, &result.data_type)?;
+                    emit_data_type(collector, &result.data_type, scalar_type)?;
                 }
                 2 => {
                     collector.write_fmt(format_args!("({}, {}: ", parameters[0].name, parameters[1].name))?;
-                    emit_data_type(collector, &parameters[1].data_type)?;
+                    emit_data_type(collector, &parameters[1].data_type, scalar_type)?;
                     collector.write_all(b") -> ")?;
-                    emit_data_type(collector, &result.data_type)?;
+                    emit_data_type(collector, &result.data_type, scalar_type)?;
                 }
                 _ => unreachable!(),
             }
@@ -645,19 +793,23 @@ pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, inden
                 emit_indentation(collector, indentation + 2)?;
                 if i + 1 == body.len() {
                     if let AstNode::ReturnStatement { expression } = statement {
-                        emit_expression(collector, expression)?;
+                        emit_expression(collector, expression, scalar_type)?;
                         collector.write_all(b"\n")?;
                         break;
                     }
                 }
-                emit_code(collector, statement, indentation + 2)?;
+                let is_mutable = match statement {
+                    AstNode::VariableAssignment { name, .. } => is_reassigned(body, name),
+                    _ => true,
+                };
+                emit_statement(collector, statement, scalar_type, indentation + 2, is_mutable)?;
             }
             emit_indentation(collector, indentation + 1)?;
             collector.write_all(b"}\n}\n\n")?;
             // Emit math trait impls
             match result.name {
                 "Add" | "Sub" | "Mul" | "Div" => {
-                    emit_assign_trait(collector, result, parameters)?;
+                    emit_assign_trait(collector, result, parameters, scalar_type)?;
                 }
                 _ => {}
             }
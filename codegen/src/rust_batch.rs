@@ -0,0 +1,675 @@
+/// Code generator for the batch classes in Rust
+///
+/// A batch class stores four instances of a class as a structure of arrays:
+/// Every element is a SIMD vector with one lane per instance, so that no lanes are wasted on padding.
+/// The trait implementations are the same as those of the classes,
+/// but every SIMD vector expression is expanded into one expression per component (like in the C backend).
+use crate::{
+    ast::{AstNode, DataType, Expression, ExpressionContent, Parameter, ScalarType},
+    emit::{camel_to_snake_case, emit_element_name, emit_indentation},
+};
+
+/// Number of instances in a batch, which is the number of lanes of the SIMD vectors
+pub const BATCH_SIZE: usize = 4;
+
+/// Emit the SIMD vector which holds one element of all instances in a batch
+fn emit_lane_type<W: std::io::Write>(collector: &mut W, scalar_type: ScalarType) -> std::io::Result<()> {
+    match scalar_type {
+        ScalarType::F32 => collector.write_fmt(format_args!("Simd32x{}", BATCH_SIZE)),
+        ScalarType::F64 => collector.write_fmt(format_args!("Simd64x{}", BATCH_SIZE)),
+    }
+}
+
+/// Emit ast::DataType to the Rust type of a batch
+fn emit_data_type<W: std::io::Write>(collector: &mut W, data_type: &DataType, scalar_type: ScalarType) -> std::io::Result<()> {
+    match data_type {
+        DataType::Integer => collector.write_all(b"isize"), // Integers are the same for all instances
+        DataType::SimdVector(_size) => emit_lane_type(collector, scalar_type), // Vectors are expanded per component
+        DataType::MultiVector(class) if class.is_scalar() => emit_lane_type(collector, scalar_type), // Scalar MV as lanes
+        DataType::MultiVector(class) => collector.write_fmt(format_args!("{}X{}", class.class_name, BATCH_SIZE)), // MV to batch class
+    }
+}
+
+/// Emit a floating point constant, splatted to all lanes
+fn emit_literal<W: std::io::Write>(collector: &mut W, value: isize, scalar_type: ScalarType) -> std::io::Result<()> {
+    emit_lane_type(collector, scalar_type)?;
+    collector.write_fmt(format_args!("::from({:.1})", value as f32))
+}
+
+/// Checks if the given component of an expression is a product with a constant zero
+///
+/// The products of the classes are computed in SIMD vectors, which mask unused terms by multiplying them with zero.
+/// In a batch these terms would waste whole SIMD vectors, so they are skipped instead.
+fn is_zero(expression: &Expression, component: usize) -> bool {
+    match &expression.content {
+        ExpressionContent::Constant(DataType::SimdVector(_size), values) => values[if values.len() == 1 { 0 } else { component }] == 0,
+        ExpressionContent::Swizzle(inner_expression, indices) => is_zero(inner_expression, indices[component]),
+        ExpressionContent::Multiply(lhs, rhs) => is_zero(lhs, component) || is_zero(rhs, component),
+        ExpressionContent::Add(lhs, rhs) | ExpressionContent::Subtract(lhs, rhs) => is_zero(lhs, component) && is_zero(rhs, component),
+        ExpressionContent::MultiplyAdd(a, b, c) => (is_zero(a, component) || is_zero(b, component)) && is_zero(c, component),
+        _ => false,
+    }
+}
+
+/// Emit the access of a component of a group
+fn emit_group_access<W: std::io::Write>(
+    collector: &mut W,
+    inner_expression: &Expression,
+    array_index: usize,
+    component_index: usize,
+    scalar_type: ScalarType,
+) -> std::io::Result<()> {
+    emit_expression(collector, inner_expression, 0, scalar_type)?;
+    if !inner_expression.is_scalar() {
+        collector.write_fmt(format_args!(".group{}()[{}]", array_index, component_index))?;
+    }
+    Ok(())
+}
+
+/// Emit the given component of an ast::Expression to Rust code
+fn emit_expression<W: std::io::Write>(collector: &mut W, expression: &Expression, component: usize, scalar_type: ScalarType) -> std::io::Result<()> {
+    if is_zero(expression, component) {
+        return emit_literal(collector, 0, scalar_type);
+    }
+    match &expression.content {
+        ExpressionContent::None => unreachable!(),
+
+        // Variable reference
+        ExpressionContent::Variable(_data_type, name) => {
+            collector.write_all(name.as_bytes())?;
+        }
+
+        // Method call on an object
+        ExpressionContent::InvokeInstanceMethod(_result_class, inner_expression, method_name, _, arguments) => {
+            emit_expression(collector, inner_expression, 0, scalar_type)?;
+            collector.write_all(b".")?;
+            camel_to_snake_case(collector, method_name)?;
+            collector.write_all(b"(")?;
+            for (i, (_argument_class, argument)) in arguments.iter().enumerate() {
+                if i > 0 {
+                    collector.write_all(b", ")?;
+                }
+                emit_expression(collector, argument, 0, scalar_type)?;
+            }
+            collector.write_all(b")")?;
+        }
+
+        // Special case for scalar constructor - just pass through the argument
+        ExpressionContent::InvokeClassMethod(class, "Constructor", arguments) if class.is_scalar() => {
+            emit_expression(collector, &arguments[0].1, 0, scalar_type)?;
+        }
+
+        // Object constructor with explicit initialization of every component of the group fields
+        ExpressionContent::InvokeClassMethod(class, "Constructor", arguments) => {
+            collector.write_fmt(format_args!(
+                "{}X{} {{ groups: {}X{}Groups {{ ",
+                class.class_name, BATCH_SIZE, class.class_name, BATCH_SIZE
+            ))?;
+            for (j, (group, (_argument_class, argument))) in class.grouped_basis.iter().zip(arguments.iter()).enumerate() {
+                if j > 0 {
+                    collector.write_all(b", ")?;
+                }
+                collector.write_fmt(format_args!("g{}: [", j))?;
+                for i in 0..group.len() {
+                    if i > 0 {
+                        collector.write_all(b", ")?;
+                    }
+                    emit_expression(collector, argument, i, scalar_type)?;
+                }
+                collector.write_all(b"]")?;
+            }
+            collector.write_all(b" } }")?;
+        }
+
+        // Static class method call
+        ExpressionContent::InvokeClassMethod(class, method_name, arguments) => {
+            emit_data_type(collector, &DataType::MultiVector(class), scalar_type)?;
+            collector.write_all(b"::")?;
+            camel_to_snake_case(collector, method_name)?;
+            collector.write_all(b"(")?;
+            for (i, (_argument_class, argument)) in arguments.iter().enumerate() {
+                if i > 0 {
+                    collector.write_all(b", ")?;
+                }
+                emit_expression(collector, argument, 0, scalar_type)?;
+            }
+            collector.write_all(b")")?;
+        }
+
+        // Type conversion using Rust's into() method
+        ExpressionContent::Conversion(_source_class, _destination_class, inner_expression) => {
+            emit_expression(collector, inner_expression, 0, scalar_type)?;
+            collector.write_all(b".into()")?;
+        }
+
+        // Conditional expression, the condition is the same for all instances
+        ExpressionContent::Select(condition_expression, then_expression, else_expression) => {
+            collector.write_all(b"if ")?;
+            emit_expression(collector, condition_expression, 0, scalar_type)?;
+            collector.write_all(b" { ")?;
+            emit_expression(collector, then_expression, component, scalar_type)?;
+            collector.write_all(b" } else { ")?;
+            emit_expression(collector, else_expression, component, scalar_type)?;
+            collector.write_all(b" }")?;
+        }
+
+        // Group of a multivector, a group of size 1 is splatted
+        ExpressionContent::Access(inner_expression, array_index) => {
+            let component_index = if expression.size > 1 { component } else { 0 };
+            emit_group_access(collector, inner_expression, *array_index, component_index, scalar_type)?;
+        }
+
+        // Component reordering
+        ExpressionContent::Swizzle(inner_expression, indices) => {
+            emit_expression(collector, inner_expression, indices[component], scalar_type)?;
+        }
+
+        // Component of a potentially different group, a single index pair is splatted
+        ExpressionContent::Gather(inner_expression, indices) => {
+            let (array_index, component_index) = indices[if indices.len() == 1 { 0 } else { component }];
+            emit_group_access(collector, inner_expression, array_index, component_index, scalar_type)?;
+        }
+
+        // Constant value, a single value is splatted
+        ExpressionContent::Constant(data_type, values) => {
+            let value = values[if values.len() == 1 { 0 } else { component }];
+            match data_type {
+                DataType::Integer => collector.write_fmt(format_args!("{}", value))?,
+                DataType::SimdVector(_size) => emit_literal(collector, value, scalar_type)?,
+                _ => unreachable!(),
+            }
+        }
+
+        // Square root function as a method call
+        ExpressionContent::SquareRoot(inner_expression) => {
+            // Method calls bind stronger than the binary operators
+            let is_binary_operation = matches!(
+                inner_expression.content,
+                ExpressionContent::Add(_, _)
+                    | ExpressionContent::Subtract(_, _)
+                    | ExpressionContent::Multiply(_, _)
+                    | ExpressionContent::Divide(_, _)
+            );
+            if is_binary_operation {
+                collector.write_all(b"(")?;
+            }
+            emit_expression(collector, inner_expression, component, scalar_type)?;
+            collector.write_all(if is_binary_operation { b").sqrt()" } else { b".sqrt()" })?;
+        }
+
+        // Sums which contain products with zero
+        ExpressionContent::Add(lhs, rhs) | ExpressionContent::Subtract(lhs, rhs) if is_zero(rhs, component) => {
+            emit_expression(collector, lhs, component, scalar_type)?;
+        }
+        ExpressionContent::Add(lhs, rhs) if is_zero(lhs, component) => {
+            emit_expression(collector, rhs, component, scalar_type)?;
+        }
+
+        // Fused multiply-adds which contain products with zero
+        ExpressionContent::MultiplyAdd(a, b, c) if is_zero(a, component) || is_zero(b, component) => {
+            emit_expression(collector, c, component, scalar_type)?;
+        }
+        ExpressionContent::MultiplyAdd(a, b, c) if is_zero(c, component) => {
+            emit_expression(collector, a, component, scalar_type)?;
+            collector.write_all(b" * ")?;
+            emit_expression(collector, b, component, scalar_type)?;
+        }
+        ExpressionContent::MultiplyAdd(a, b, c) => {
+            emit_lane_type(collector, scalar_type)?;
+            collector.write_all(b"::mul_add(")?;
+            emit_expression(collector, a, component, scalar_type)?;
+            collector.write_all(b", ")?;
+            emit_expression(collector, b, component, scalar_type)?;
+            collector.write_all(b", ")?;
+            emit_expression(collector, c, component, scalar_type)?;
+            collector.write_all(b")")?;
+        }
+
+        // Binary operations
+        ExpressionContent::Add(lhs, rhs)
+        | ExpressionContent::Subtract(lhs, rhs)
+        | ExpressionContent::Multiply(lhs, rhs)
+        | ExpressionContent::Divide(lhs, rhs)
+        | ExpressionContent::LessThan(lhs, rhs)
+        | ExpressionContent::Equal(lhs, rhs)
+        | ExpressionContent::LogicAnd(lhs, rhs)
+        | ExpressionContent::BitShiftRight(lhs, rhs) => {
+            emit_expression(collector, lhs, component, scalar_type)?;
+            collector.write_all(match expression.content {
+                ExpressionContent::Add(_, _) => b" + ",
+                ExpressionContent::Subtract(_, _) => b" - ",
+                ExpressionContent::Multiply(_, _) => b" * ",
+                ExpressionContent::Divide(_, _) => b" / ",
+                ExpressionContent::LessThan(_, _) => b" < ",
+                ExpressionContent::Equal(_, _) => b" == ",
+                ExpressionContent::LogicAnd(_, _) => b" & ",
+                ExpressionContent::BitShiftRight(_, _) => b" >> ",
+                _ => unreachable!(),
+            })?;
+            emit_expression(collector, rhs, component, scalar_type)?;
+        }
+    }
+    Ok(())
+}
+
+/// Emit OpAssign (eg AddAssign, MulAssign etc) trait implementations for algebraic operations
+fn emit_assign_trait<W: std::io::Write>(
+    collector: &mut W,
+    result: &Parameter,
+    parameters: &[Parameter],
+    scalar_type: ScalarType,
+) -> std::io::Result<()> {
+    // Skip if operating on different types
+    if result.multi_vector_class() != parameters[0].multi_vector_class() {
+        return Ok(());
+    }
+
+    // Generate assignment trait implementation (e.g., AddAssign, MulAssign)
+    collector.write_fmt(format_args!("impl {}Assign<", result.name))?;
+    emit_data_type(collector, &parameters[1].data_type, scalar_type)?;
+    collector.write_all(b"> for ")?;
+    emit_data_type(collector, &parameters[0].data_type, scalar_type)?;
+    collector.write_all(b" {\n    fn ")?;
+    camel_to_snake_case(collector, result.name)?;
+    collector.write_all(b"_assign(&mut self, other: ")?;
+    emit_data_type(collector, &parameters[1].data_type, scalar_type)?;
+    collector.write_all(b") {\n        *self = (*self).")?;
+    camel_to_snake_case(collector, result.name)?;
+    collector.write_all(b"(other);\n    }\n}\n\n")
+}
+
+/// Main code generation function for emitting ast nodes to the batch classes in rust code
+///
+/// Emits nothing for the preamble, so the output has to be appended to that of [rust::emit_code](crate::rust::emit_code).
+pub fn emit_code<W: std::io::Write>(collector: &mut W, ast_node: &AstNode, scalar_type: ScalarType, indentation: usize) -> std::io::Result<()> {
+    match &ast_node {
+        AstNode::None => {}
+        AstNode::Preamble { .. } => {}
+
+        // Class definition - generates the batch struct definition and implementations
+        AstNode::ClassDefinition { class } => {
+            // Scalars in a batch are plain SIMD vectors
+            if class.is_scalar() {
+                return Ok(());
+            }
+            let batch_name = format!("{}X{}", class.class_name, BATCH_SIZE);
+            let element_count = class.grouped_basis.iter().fold(0, |a, b| a + b.len());
+
+            // Generate inner Groups struct, with one SIMD vector per element
+            emit_indentation(collector, indentation)?;
+            collector.write_fmt(format_args!("#[derive(Clone, Copy)]\n#[repr(C)]\nstruct {}Groups {{\n", batch_name))?;
+            for (j, group) in class.grouped_basis.iter().enumerate() {
+                emit_indentation(collector, indentation + 1)?;
+                collector.write_all(b"/// ")?;
+                for (i, element) in group.iter().enumerate() {
+                    if i > 0 {
+                        collector.write_all(b", ")?;
+                    }
+                    collector.write_fmt(format_args!("{}", element))?;
+                }
+                collector.write_all(b"\n")?;
+                emit_indentation(collector, indentation + 1)?;
+                collector.write_fmt(format_args!("g{}: [", j))?;
+                emit_lane_type(collector, scalar_type)?;
+                collector.write_fmt(format_args!("; {}],\n", group.len()))?;
+            }
+            collector.write_all(b"}\n\n")?;
+
+            // Generate main batch class as a union of groups and raw elements, which have no padding in between
+            emit_indentation(collector, indentation)?;
+            collector.write_fmt(format_args!(
+                "/// Batch of {} [{}]s as a structure of arrays, with one lane per instance\n",
+                BATCH_SIZE, class.class_name
+            ))?;
+            emit_indentation(collector, indentation)?;
+            collector.write_fmt(format_args!("#[derive(Clone, Copy)]\n#[repr(C)]\npub union {} {{\n", batch_name))?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_fmt(format_args!("groups: {}Groups,\n", batch_name))?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_all(b"/// ")?;
+            for (i, element) in class.grouped_basis.iter().flatten().enumerate() {
+                if i > 0 {
+                    collector.write_all(b", ")?;
+                }
+                collector.write_fmt(format_args!("{}", element))?;
+            }
+            collector.write_all(b"\n")?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_all(b"elements: [")?;
+            emit_lane_type(collector, scalar_type)?;
+            collector.write_fmt(format_args!("; {}],\n", element_count))?;
+            emit_indentation(collector, indentation)?;
+            collector.write_all(b"}\n\n")?;
+
+            // Start implementation block
+            emit_indentation(collector, indentation)?;
+            collector.write_fmt(format_args!("impl {} {{\n", batch_name))?;
+
+            // Generate constructor
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_all(b"#[allow(clippy::too_many_arguments)]\n")?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_all(b"pub const fn new(")?;
+            for (i, element) in class.grouped_basis.iter().flatten().enumerate() {
+                if i > 0 {
+                    collector.write_all(b", ")?;
+                }
+                emit_element_name(collector, element)?;
+                collector.write_all(b": ")?;
+                emit_lane_type(collector, scalar_type)?;
+            }
+            collector.write_all(b") -> Self {\n")?;
+            emit_indentation(collector, indentation + 2)?;
+            collector.write_all(b"Self { elements: [")?;
+            for (i, element) in class.grouped_basis.iter().flatten().enumerate() {
+                if i > 0 {
+                    collector.write_all(b", ")?;
+                }
+                emit_element_name(collector, element)?;
+            }
+            collector.write_all(b"] }\n")?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_all(b"}\n")?;
+
+            // Generate accessor methods for each group
+            for (j, group) in class.grouped_basis.iter().enumerate() {
+                // Immutable accessor
+                emit_indentation(collector, indentation + 1)?;
+                collector.write_all(b"#[inline(always)]\n")?;
+                emit_indentation(collector, indentation + 1)?;
+                collector.write_fmt(format_args!("pub fn group{}(&self) -> [", j))?;
+                emit_lane_type(collector, scalar_type)?;
+                collector.write_fmt(format_args!("; {}] {{\n", group.len()))?;
+                emit_indentation(collector, indentation + 2)?;
+                collector.write_fmt(format_args!("unsafe {{ self.groups.g{} }}\n", j))?;
+                emit_indentation(collector, indentation + 1)?;
+                collector.write_all(b"}\n")?;
+
+                // Mutable accessor
+                emit_indentation(collector, indentation + 1)?;
+                collector.write_all(b"#[inline(always)]\n")?;
+                emit_indentation(collector, indentation + 1)?;
+                collector.write_fmt(format_args!("pub fn group{}_mut(&mut self) -> &mut [", j))?;
+                emit_lane_type(collector, scalar_type)?;
+                collector.write_fmt(format_args!("; {}] {{\n", group.len()))?;
+                emit_indentation(collector, indentation + 2)?;
+                collector.write_fmt(format_args!("unsafe {{ &mut self.groups.g{} }}\n", j))?;
+                emit_indentation(collector, indentation + 1)?;
+                collector.write_all(b"}\n")?;
+            }
+            emit_indentation(collector, indentation)?;
+            collector.write_all(b"}\n\n")?;
+
+            // Implement Index trait
+            emit_indentation(collector, indentation)?;
+            collector.write_fmt(format_args!("impl core::ops::Index<usize> for {} {{\n", batch_name))?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_all(b"type Output = ")?;
+            emit_lane_type(collector, scalar_type)?;
+            collector.write_all(b";\n\n")?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_all(b"fn index(&self, index: usize) -> &Self::Output {\n")?;
+            emit_indentation(collector, indentation + 2)?;
+            collector.write_all(b"unsafe { &self.elements[index] }\n")?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_all(b"}\n")?;
+            emit_indentation(collector, indentation)?;
+            collector.write_all(b"}\n\n")?;
+
+            // Implement IndexMut trait
+            emit_indentation(collector, indentation)?;
+            collector.write_fmt(format_args!("impl core::ops::IndexMut<usize> for {} {{\n", batch_name))?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_all(b"fn index_mut(&mut self, index: usize) -> &mut Self::Output {\n")?;
+            emit_indentation(collector, indentation + 2)?;
+            collector.write_all(b"unsafe { &mut self.elements[index] }\n")?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_all(b"}\n")?;
+            emit_indentation(collector, indentation)?;
+            collector.write_all(b"}\n\n")?;
+
+            // Implement conversion from a single instance, which is splatted to all lanes
+            emit_indentation(collector, indentation)?;
+            collector.write_fmt(format_args!("impl core::convert::From<{}> for {} {{\n", class.class_name, batch_name))?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_fmt(format_args!("fn from(instance: {}) -> Self {{\n", class.class_name))?;
+            emit_indentation(collector, indentation + 2)?;
+            collector.write_all(b"Self { elements: [")?;
+            for i in 0..element_count {
+                if i > 0 {
+                    collector.write_all(b", ")?;
+                }
+                emit_lane_type(collector, scalar_type)?;
+                collector.write_fmt(format_args!("::from(instance[{}])", i))?;
+            }
+            collector.write_all(b"] }\n")?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_all(b"}\n")?;
+            emit_indentation(collector, indentation)?;
+            collector.write_all(b"}\n\n")?;
+
+            // Implement conversion from an array of instances (array of structures to structure of arrays)
+            emit_indentation(collector, indentation)?;
+            collector.write_fmt(format_args!(
+                "impl core::convert::From<[{}; {}]> for {} {{\n",
+                class.class_name, BATCH_SIZE, batch_name
+            ))?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_fmt(format_args!("fn from(instances: [{}; {}]) -> Self {{\n", class.class_name, BATCH_SIZE))?;
+            emit_indentation(collector, indentation + 2)?;
+            collector.write_all(b"Self { elements: [")?;
+            for i in 0..element_count {
+                if i > 0 {
+                    collector.write_all(b", ")?;
+                }
+                emit_lane_type(collector, scalar_type)?;
+                collector.write_all(b"::from([")?;
+                for lane in 0..BATCH_SIZE {
+                    if lane > 0 {
+                        collector.write_all(b", ")?;
+                    }
+                    collector.write_fmt(format_args!("instances[{}][{}]", lane, i))?;
+                }
+                collector.write_all(b"])")?;
+            }
+            collector.write_all(b"] }\n")?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_all(b"}\n")?;
+            emit_indentation(collector, indentation)?;
+            collector.write_all(b"}\n\n")?;
+
+            // Implement conversion to an array of instances (structure of arrays to array of structures)
+            emit_indentation(collector, indentation)?;
+            collector.write_fmt(format_args!(
+                "impl core::convert::From<{}> for [{}; {}] {{\n",
+                batch_name, class.class_name, BATCH_SIZE
+            ))?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_fmt(format_args!("fn from(batch: {}) -> Self {{\n", batch_name))?;
+            emit_indentation(collector, indentation + 2)?;
+            collector.write_all(b"[")?;
+            for lane in 0..BATCH_SIZE {
+                if lane > 0 {
+                    collector.write_all(b", ")?;
+                }
+                collector.write_fmt(format_args!("{}::new(", class.class_name))?;
+                for i in 0..element_count {
+                    if i > 0 {
+                        collector.write_all(b", ")?;
+                    }
+                    collector.write_fmt(format_args!("batch[{}][{}]", i, lane))?;
+                }
+                collector.write_all(b")")?;
+            }
+            collector.write_all(b"]\n")?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_all(b"}\n")?;
+            emit_indentation(collector, indentation)?;
+            collector.write_all(b"}\n\n")?;
+
+            // Implement Debug trait for nicer formatting
+            emit_indentation(collector, indentation)?;
+            collector.write_fmt(format_args!("impl core::fmt::Debug for {} {{\n", batch_name))?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_all(b"fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {\n")?;
+            emit_indentation(collector, indentation + 2)?;
+            collector.write_all(b"formatter\n")?;
+            emit_indentation(collector, indentation + 3)?;
+            collector.write_fmt(format_args!(".debug_struct(\"{}\")\n", batch_name))?;
+            for (i, element) in class.grouped_basis.iter().flatten().enumerate() {
+                emit_indentation(collector, indentation + 3)?;
+                collector.write_fmt(format_args!(".field(\"{}\", &self[{}])\n", element, i))?;
+            }
+            emit_indentation(collector, indentation + 3)?;
+            collector.write_all(b".finish()\n")?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_all(b"}\n")?;
+            emit_indentation(collector, indentation)?;
+            collector.write_all(b"}\n\n")?;
+
+            // Implement bytemuck traits, batches never contain padding
+            emit_indentation(collector, indentation)?;
+            collector.write_all(b"cfg_bytemuck! {\n")?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_fmt(format_args!("unsafe impl bytemuck::Zeroable for {} {{}}\n\n", batch_name))?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_fmt(format_args!("unsafe impl bytemuck::Pod for {} {{}}\n", batch_name))?;
+            emit_indentation(collector, indentation)?;
+            collector.write_all(b"}\n\n")?;
+        }
+
+        // Return statement
+        AstNode::ReturnStatement { expression } => {
+            collector.write_all(b"return ")?;
+            emit_expression(collector, expression, 0, scalar_type)?;
+            collector.write_all(b";\n")?;
+        }
+
+        // Variable assignment
+        AstNode::VariableAssignment { name, data_type, expression } => {
+            if let Some(data_type) = data_type {
+                collector.write_fmt(format_args!("let mut {}", name))?;
+                collector.write_all(b": ")?;
+                emit_data_type(collector, data_type, scalar_type)?;
+            } else {
+                collector.write_fmt(format_args!("{}", name))?;
+            }
+            collector.write_all(b" = ")?;
+            emit_expression(collector, expression, 0, scalar_type)?;
+            collector.write_all(b";\n")?;
+        }
+
+        // Control flow statements, the conditions are the same for all instances
+        AstNode::IfThenBlock { condition, body } | AstNode::WhileLoopBlock { condition, body } => {
+            collector.write_all(match &ast_node {
+                AstNode::IfThenBlock { .. } => b"if ",
+                AstNode::WhileLoopBlock { .. } => b"while ",
+                _ => unreachable!(),
+            })?;
+            emit_expression(collector, condition, 0, scalar_type)?;
+            collector.write_all(b" {\n")?;
+            for statement in body.iter() {
+                emit_indentation(collector, indentation + 1)?;
+                emit_code(collector, statement, scalar_type, indentation + 1)?;
+            }
+            emit_indentation(collector, indentation)?;
+            collector.write_all(b"}\n")?;
+        }
+
+        // Trait implementation (for operator overloading, conversion, etc.)
+        AstNode::TraitImplementation { result, parameters, body } => {
+            // Skip the elementary functions, which branch on the values of each instance
+            if matches!(result.name, "Exp" | "Ln" | "Powf" | "Sqrt" | "Normalize") {
+                return Ok(());
+            }
+
+            // Skip purely scalar implementations with no multi-vector involvement
+            if result.data_type.is_scalar()
+                && !parameters
+                    .iter()
+                    .any(|parameter| matches!(parameter.data_type, DataType::MultiVector(class) if !class.is_scalar()))
+            {
+                return Ok(());
+            }
+
+            // Generate trait implementation header
+            collector.write_fmt(format_args!("impl {}", result.name))?;
+
+            // Determine which type to implement the trait for
+            let impl_for = match parameters.len() {
+                0 => &result.data_type,
+                1 if result.name == "Into" => {
+                    collector.write_all(b"<")?;
+                    emit_data_type(collector, &result.data_type, scalar_type)?;
+                    collector.write_all(b">")?;
+                    &parameters[0].data_type
+                }
+                1 => &parameters[0].data_type,
+                2 if !matches!(parameters[1].data_type, DataType::MultiVector(_)) => &parameters[0].data_type,
+                2 => {
+                    collector.write_all(b"<")?;
+                    emit_data_type(collector, &parameters[1].data_type, scalar_type)?;
+                    collector.write_all(b">")?;
+                    &parameters[0].data_type
+                }
+                _ => unreachable!(),
+            };
+            collector.write_all(b" for ")?;
+            emit_data_type(collector, impl_for, scalar_type)?;
+            collector.write_all(b" {\n")?;
+
+            // Add associated Output type for operators
+            if !parameters.is_empty() && result.name != "Into" {
+                emit_indentation(collector, indentation + 1)?;
+                collector.write_all(b"type Output = ")?;
+                emit_data_type(collector, &result.data_type, scalar_type)?;
+                collector.write_all(b";\n\n")?;
+            }
+
+            // Emit function for operators, which the `dispatch` feature inlines so that it is compiled for the target features of the caller
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_all(b"inline_for_dispatch! {\n")?;
+            emit_indentation(collector, indentation + 2)?;
+            collector.write_all(b"fn ")?;
+            camel_to_snake_case(collector, result.name)?;
+            match parameters.len() {
+                0 => collector.write_all(b"() -> Self")?,
+                1 => {
+                    collector.write_fmt(format_args!("({}) -> ", parameters[0].name))?;
+                    emit_data_type(collector, &result.data_type, scalar_type)?;
+                }
+                2 => {
+                    collector.write_fmt(format_args!("({}, {}: ", parameters[0].name, parameters[1].name))?;
+                    emit_data_type(collector, &parameters[1].data_type, scalar_type)?;
+                    collector.write_all(b") -> ")?;
+                    emit_data_type(collector, &result.data_type, scalar_type)?;
+                }
+                _ => unreachable!(),
+            }
+            collector.write_all(b" {\n")?;
+            for (i, statement) in body.iter().enumerate() {
+                emit_indentation(collector, indentation + 3)?;
+                if i + 1 == body.len() {
+                    if let AstNode::ReturnStatement { expression } = statement {
+                        emit_expression(collector, expression, 0, scalar_type)?;
+                        collector.write_all(b"\n")?;
+                        break;
+                    }
+                }
+                emit_code(collector, statement, scalar_type, indentation + 3)?;
+            }
+            emit_indentation(collector, indentation + 2)?;
+            collector.write_all(b"}\n")?;
+            emit_indentation(collector, indentation + 1)?;
+            collector.write_all(b"}\n}\n\n")?;
+            // Emit math trait impls
+            match result.name {
+                "Add" | "Sub" | "Mul" | "Div" => {
+                    emit_assign_trait(collector, result, parameters, scalar_type)?;
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,155 @@
+//! Runtime dispatch of batch operations to the SIMD extensions of the CPU, enabled by the `dispatch` feature
+//!
+//! The [simd](crate::simd) module selects its implementation by the target features at compile time,
+//! so a binary built for baseline x86_64 never uses more than SSE2.
+//! [dispatch!](crate::dispatch!) instead compiles a function once per [FeatureLevel] and calls the version of the best level which the CPU supports.
+//!
+//! Only code which is inlined into the function is compiled for the feature level,
+//! which this feature ensures for the operations of the batch classes (e.g. [MotorX4](crate::ppga3d::MotorX4)).
+//! The intrinsics of the [simd](crate::simd) module are still chosen at compile time though,
+//! so [mul_add](crate::simd::Simd32x4::mul_add) only fuses if `fma` is enabled for the whole crate:
+//!
+//! ```
+//! use geometric_algebra::{ppga3d::*, *};
+//!
+//! geometric_algebra::dispatch! {
+//!     /// Transforms the points by the motor
+//!     fn transform(motor: MotorX4, points: &[PointX4], transformed: &mut [PointX4]) {
+//!         for (point, transformed) in points.iter().zip(transformed.iter_mut()) {
+//!             *transformed = motor.transformation(*point);
+//!         }
+//!     }
+//! }
+//!
+//! let motor = MotorX4::from(Motor::new(1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 2.0, 3.0));
+//! let points = vec![PointX4::from(Point::new(1.0, 0.0, 0.0, 0.0)); 1024];
+//! let mut transformed = points.clone();
+//! transform(motor, &points, &mut transformed);
+//! ```
+
+/// Set of target features which the functions of [dispatch!](crate::dispatch!) are compiled for
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum FeatureLevel {
+    /// The target features the crate is compiled with
+    Baseline,
+    /// SSE4.1, like x86-64-v2
+    Sse41,
+    /// AVX2, which x86-64-v3 extends by FMA and others
+    Avx2,
+}
+
+/// Detects the best [FeatureLevel] which the CPU supports
+///
+/// The detection is cached by the standard library, so this is cheap to call repeatedly.
+pub fn feature_level() -> FeatureLevel {
+    #[cfg(test)]
+    if let Some(level) = tests::LEVEL.with(|level| level.get()) {
+        return level;
+    }
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if is_x86_feature_detected!("avx2") {
+            return FeatureLevel::Avx2;
+        }
+        if is_x86_feature_detected!("sse4.1") {
+            return FeatureLevel::Sse41;
+        }
+    }
+    FeatureLevel::Baseline
+}
+
+/// Defines a function which is compiled once per [FeatureLevel] and dispatches to the best one which the CPU supports
+///
+/// The parameters have to be plain identifiers and the function can not be generic.
+#[macro_export]
+macro_rules! dispatch {
+    ($(#[$attribute:meta])* $visibility:vis fn $name:ident($($parameter:ident: $type:ty),* $(,)?) $(-> $result:ty)? $body:block) => {
+        $(#[$attribute])*
+        $visibility fn $name($($parameter: $type),*) $(-> $result)? {
+            #[inline(always)]
+            fn body($($parameter: $type),*) $(-> $result)? $body
+
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            #[target_feature(enable = "sse4.1")]
+            unsafe fn sse41($($parameter: $type),*) $(-> $result)? {
+                body($($parameter),*)
+            }
+
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            #[target_feature(enable = "avx2")]
+            unsafe fn avx2($($parameter: $type),*) $(-> $result)? {
+                body($($parameter),*)
+            }
+
+            // Safety: The CPU supports the target features of the detected level
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            match $crate::dispatch::feature_level() {
+                $crate::dispatch::FeatureLevel::Avx2 => return unsafe { avx2($($parameter),*) },
+                $crate::dispatch::FeatureLevel::Sse41 => return unsafe { sse41($($parameter),*) },
+                $crate::dispatch::FeatureLevel::Baseline => {}
+            }
+            body($($parameter),*)
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FeatureLevel;
+    use crate::{ppga3d::*, *};
+
+    std::thread_local! {
+        /// Overrides the detected [FeatureLevel], which must not exceed it
+        pub static LEVEL: core::cell::Cell<Option<FeatureLevel>> = const { core::cell::Cell::new(None) };
+    }
+
+    crate::dispatch! {
+        fn transform(motor: MotorX4, points: &[PointX4], transformed: &mut [PointX4]) {
+            for (point, transformed) in points.iter().zip(transformed.iter_mut()) {
+                *transformed = motor.transformation(*point);
+            }
+        }
+    }
+
+    #[test]
+    fn levels_match_scalar() {
+        let motor = Line::new(0.3, -0.2, 0.5, 0.7, 0.1, -0.4).exp();
+        let points: Vec<[Point; 4]> = (0..8)
+            .map(|i| {
+                let i = i as f32;
+                [
+                    Point::new(1.0, i, -2.0, 0.5),
+                    Point::new(2.0, 0.25, i * 0.5, -1.0),
+                    Point::new(0.5, -i, 3.0, i),
+                    Point::new(1.0, 0.0, 0.0, 0.0),
+                ]
+            })
+            .collect();
+        let batches: Vec<PointX4> = points.iter().map(|points| PointX4::from(*points)).collect();
+        let detected = super::feature_level();
+        for level in [FeatureLevel::Baseline, FeatureLevel::Sse41, FeatureLevel::Avx2] {
+            if level > detected {
+                continue;
+            }
+            LEVEL.with(|cell| cell.set(Some(level)));
+            let mut transformed = batches.clone();
+            transform(MotorX4::from(motor), &batches, &mut transformed);
+            LEVEL.with(|cell| cell.set(None));
+            for (points, transformed) in points.iter().zip(transformed) {
+                for (point, transformed) in points.iter().zip(<[Point; 4]>::from(transformed)) {
+                    let expected: [f32; 4] = motor.transformation(*point).into();
+                    let transformed: [f32; 4] = transformed.into();
+                    for (a, b) in transformed.iter().zip(expected.iter()) {
+                        assert!(
+                            (a - b).abs() <= 1.0e-5 * (1.0 + b.abs()),
+                            "{:?}: {:?} != {:?}",
+                            level,
+                            transformed,
+                            expected
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,3 +1,8 @@
+#![allow(clippy::macro_metavars_in_unsafe)]
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(feature = "portable_simd", feature(portable_simd))]
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("either the `std` or the `libm` feature is required for the elementary functions of the scalars");
 pub mod epga1d;
 pub mod ppga1d;
 pub mod hpga1d;
@@ -7,317 +12,678 @@ pub mod hpga2d;
 pub mod epga3d;
 pub mod ppga3d;
 pub mod hpga3d;
+pub mod epga1d_f64;
+pub mod ppga2d_f64;
+pub mod ppga3d_f64;
+pub mod cga3d;
+pub mod sta;
+pub mod vga3d;
 pub mod simd;
 pub mod polynomial;
+#[cfg(feature = "dispatch")]
+pub mod dispatch;
+#[cfg(feature = "serde")]
+pub mod serialization;
+#[cfg(feature = "bytemuck")]
+pub use bytemuck;
+
+// The generated code wraps everything that depends on a feature of this crate into one of these macros,
+// so that the feature is checked here and not in the downstream crate which includes code generated by a build script.
+
+/// Expands to the given items if the `serde` feature is enabled
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! cfg_serde {
+    ($($item:item)*) => {
+        $($item)*
+    };
+}
+
+/// Expands to the given items if the `serde` feature is enabled
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! cfg_serde {
+    ($($item:item)*) => {};
+}
+
+/// Expands to the given items if the `bytemuck` feature is enabled
+#[cfg(feature = "bytemuck")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! cfg_bytemuck {
+    ($($item:item)*) => {
+        $($item)*
+    };
+}
+
+/// Expands to the given items if the `bytemuck` feature is enabled
+#[cfg(not(feature = "bytemuck"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! cfg_bytemuck {
+    ($($item:item)*) => {};
+}
+
+/// Marks the given function `#[inline(always)]` if the `dispatch` feature is enabled, so that it is compiled for the target features of the caller
+#[cfg(feature = "dispatch")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! inline_for_dispatch {
+    ($($function:tt)*) => {
+        #[inline(always)]
+        $($function)*
+    };
+}
+
+/// Marks the given function `#[inline(always)]` if the `dispatch` feature is enabled, so that it is compiled for the target features of the caller
+#[cfg(not(feature = "dispatch"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! inline_for_dispatch {
+    ($($function:tt)*) => {
+        $($function)*
+    };
+}
+
+macro_rules! impl_scalar {
+    ($scalar:ident) => {
+        impl Zero for $scalar {
+            fn zero() -> Self {
+                0.0
+            }
+        }
 
-impl Zero for f32 {
-    fn zero() -> Self {
-        0.0
-    }
-}
+        impl One for $scalar {
+            fn one() -> Self {
+                1.0
+            }
+        }
 
-impl One for f32 {
-    fn one() -> Self {
-        1.0
-    }
-}
+        impl Automorphism for $scalar {
+            type Output = $scalar;
 
-impl Automorphism for f32 {
-    type Output = f32;
+            fn automorphism(self) -> $scalar {
+                self
+            }
+        }
 
-    fn automorphism(self) -> f32 {
-        self
-    }
-}
+        impl Reversal for $scalar {
+            type Output = $scalar;
 
-impl Reversal for f32 {
-    type Output = f32;
+            fn reversal(self) -> $scalar {
+                self
+            }
+        }
 
-    fn reversal(self) -> f32 {
-        self
-    }
-}
+        impl Conjugation for $scalar {
+            type Output = $scalar;
 
-impl Conjugation for f32 {
-    type Output = f32;
+            fn conjugation(self) -> $scalar {
+                self
+            }
+        }
 
-    fn conjugation(self) -> f32 {
-        self
-    }
-}
+        impl GeometricProduct<$scalar> for $scalar {
+            type Output = $scalar;
 
-impl GeometricProduct<f32> for f32 {
-    type Output = f32;
+            fn geometric_product(self, other: $scalar) -> $scalar {
+                self * other
+            }
+        }
 
-    fn geometric_product(self, other: f32) -> f32 {
-        self * other
-    }
-}
+        impl OuterProduct<$scalar> for $scalar {
+            type Output = $scalar;
 
-impl OuterProduct<f32> for f32 {
-    type Output = f32;
+            fn outer_product(self, other: $scalar) -> $scalar {
+                self * other
+            }
+        }
 
-    fn outer_product(self, other: f32) -> f32 {
-        self * other
-    }
-}
+        impl InnerProduct<$scalar> for $scalar {
+            type Output = $scalar;
 
-impl InnerProduct<f32> for f32 {
-    type Output = f32;
+            fn inner_product(self, other: $scalar) -> $scalar {
+                self * other
+            }
+        }
 
-    fn inner_product(self, other: f32) -> f32 {
-        self * other
-    }
-}
+        impl LeftContraction<$scalar> for $scalar {
+            type Output = $scalar;
 
-impl LeftContraction<f32> for f32 {
-    type Output = f32;
+            fn left_contraction(self, other: $scalar) -> $scalar {
+                self * other
+            }
+        }
 
-    fn left_contraction(self, other: f32) -> f32 {
-        self * other
-    }
-}
+        impl RightContraction<$scalar> for $scalar {
+            type Output = $scalar;
 
-impl RightContraction<f32> for f32 {
-    type Output = f32;
+            fn right_contraction(self, other: $scalar) -> $scalar {
+                self * other
+            }
+        }
 
-    fn right_contraction(self, other: f32) -> f32 {
-        self * other
-    }
-}
+        impl ScalarProduct<$scalar> for $scalar {
+            type Output = $scalar;
 
-impl ScalarProduct<f32> for f32 {
-    type Output = f32;
+            fn scalar_product(self, other: $scalar) -> $scalar {
+                self * other
+            }
+        }
 
-    fn scalar_product(self, other: f32) -> f32 {
-        self * other
-    }
-}
+        impl SquaredMagnitude for $scalar {
+            type Output = $scalar;
 
-impl SquaredMagnitude for f32 {
-    type Output = f32;
+            fn squared_magnitude(self) -> $scalar {
+                self.scalar_product(self.reversal())
+            }
+        }
 
-    fn squared_magnitude(self) -> f32 {
-        self.scalar_product(self.reversal())
-    }
-}
+        impl Magnitude for $scalar {
+            type Output = $scalar;
 
-impl Magnitude for f32 {
-    type Output = f32;
+            fn magnitude(self) -> $scalar {
+                self.abs()
+            }
+        }
 
-    fn magnitude(self) -> f32 {
-        self.abs()
-    }
-}
+        impl Signum for $scalar {
+            type Output = $scalar;
 
-impl Signum for f32 {
-    type Output = f32;
+            fn signum(self) -> $scalar {
+                $scalar::signum(self)
+            }
+        }
 
-    fn signum(self) -> f32 {
-        f32::signum(self)
-    }
-}
+        impl Inverse for $scalar {
+            type Output = $scalar;
 
-impl Inverse for f32 {
-    type Output = f32;
+            fn inverse(self) -> $scalar {
+                1.0 / self
+            }
+        }
 
-    fn inverse(self) -> f32 {
-        1.0 / self
-    }
-}
+        impl GeometricQuotient<$scalar> for $scalar {
+            type Output = $scalar;
 
-impl GeometricQuotient<f32> for f32 {
-    type Output = f32;
+            fn geometric_quotient(self, other: $scalar) -> $scalar {
+                self.geometric_product(other.inverse())
+            }
+        }
 
-    fn geometric_quotient(self, other: f32) -> f32 {
-        self.geometric_product(other.inverse())
-    }
-}
+        impl Transformation<$scalar> for $scalar {
+            type Output = $scalar;
 
-impl Transformation<f32> for f32 {
-    type Output = f32;
+            fn transformation(self, other: $scalar) -> $scalar {
+                self.geometric_product(other)
+                    .geometric_product(self.reversal())
+            }
+        }
+    };
+}
+
+impl_scalar!(f32);
+impl_scalar!(f64);
+
+/// Forwards to the inherent methods of `std` or else to `libm`
+macro_rules! impl_float {
+    ($scalar:ident, $($method:ident($($argument:ident),*) => $libm:ident,)*) => {
+        impl Float for $scalar {
+            $(
+                #[inline]
+                fn $method(self, $($argument: Self),*) -> Self {
+                    #[cfg(feature = "std")]
+                    {
+                        $scalar::$method(self, $($argument),*)
+                    }
+                    #[cfg(not(feature = "std"))]
+                    {
+                        libm::$libm(self, $($argument),*)
+                    }
+                }
+            )*
+
+            #[inline]
+            fn powi(self, exponent: i32) -> Self {
+                #[cfg(feature = "std")]
+                {
+                    $scalar::powi(self, exponent)
+                }
+                #[cfg(not(feature = "std"))]
+                {
+                    self.powf(exponent as $scalar)
+                }
+            }
+        }
+    };
+}
+
+impl_float!(f32,
+    sqrt() => sqrtf,
+    sin() => sinf,
+    cos() => cosf,
+    acos() => acosf,
+    atan2(other) => atan2f,
+    sinh() => sinhf,
+    cosh() => coshf,
+    exp() => expf,
+    ln() => logf,
+    powf(exponent) => powf,
+);
+impl_float!(f64,
+    sqrt() => sqrt,
+    sin() => sin,
+    cos() => cos,
+    acos() => acos,
+    atan2(other) => atan2,
+    sinh() => sinh,
+    cosh() => cosh,
+    exp() => exp,
+    ln() => log,
+    powf(exponent) => pow,
+);
+
+/// Evaluates the functions of the square of a bivector, which [BivectorSplit] returns the coefficients of
+macro_rules! impl_bivector_split {
+    ($scalar:ident, $SimdX4:ident, $module:ident) => {
+        mod $module {
+            #[cfg(not(feature = "std"))]
+            use crate::Float;
+
+            /// Real and imaginary part
+            type Complex = ($scalar, $scalar);
+
+            /// Power series of `sinh(sqrt(x)) / sqrt(x)`
+            const SINHC_SQRT_SERIES: [$scalar; 8] = [
+                1.0,
+                1.0 / 6.0,
+                1.0 / 120.0,
+                1.0 / 5040.0,
+                1.0 / 362880.0,
+                1.0 / 39916800.0,
+                1.0 / 6227020800.0,
+                1.0 / 1307674368000.0,
+            ];
+
+            /// Power series of `acosh(1 + u) / sqrt((1 + u)² - 1)` in `u`
+            const ACOSHC_SERIES: [$scalar; 13] = [
+                1.0,
+                -1.0 / 3.0,
+                2.0 / 15.0,
+                -2.0 / 35.0,
+                8.0 / 315.0,
+                -8.0 / 693.0,
+                16.0 / 3003.0,
+                -16.0 / 6435.0,
+                128.0 / 109395.0,
+                -128.0 / 230945.0,
+                256.0 / 969969.0,
+                -256.0 / 2028117.0,
+                1024.0 / 16900975.0,
+            ];
+
+            fn add(a: Complex, b: Complex) -> Complex {
+                (a.0 + b.0, a.1 + b.1)
+            }
+
+            fn mul(a: Complex, b: Complex) -> Complex {
+                (a.0 * b.0 - a.1 * b.1, a.0 * b.1 + a.1 * b.0)
+            }
+
+            fn div(a: Complex, b: Complex) -> Complex {
+                let denominator = b.0 * b.0 + b.1 * b.1;
+                ((a.0 * b.0 + a.1 * b.1) / denominator, (a.1 * b.0 - a.0 * b.1) / denominator)
+            }
+
+            /// Principal square root
+            fn sqrt(z: Complex) -> Complex {
+                let magnitude = (z.0 * z.0 + z.1 * z.1).sqrt();
+                let imaginary = (0.5 * (magnitude - z.0)).sqrt();
+                ((0.5 * (magnitude + z.0)).sqrt(), if z.1 < 0.0 { -imaginary } else { imaginary })
+            }
+
+            /// Principal natural logarithm
+            fn ln(z: Complex) -> Complex {
+                (0.5 * (z.0 * z.0 + z.1 * z.1).ln(), z.1.atan2(z.0))
+            }
+
+            /// Value and derivative of a power series
+            fn series(coefficients: &[$scalar], x: Complex) -> [Complex; 2] {
+                coefficients.iter().rev().fold([(0.0, 0.0), (0.0, 0.0)], |[value, derivative], coefficient| {
+                    [add(mul(value, x), (*coefficient, 0.0)), add(mul(derivative, x), value)]
+                })
+            }
+
+            /// `cosh(sqrt(z))`, which is `cos(sqrt(-z))` for negative `z`
+            pub fn cosh_sqrt(z: Complex) -> Complex {
+                let root = sqrt(z);
+                (root.0.cosh() * root.1.cos(), root.0.sinh() * root.1.sin())
+            }
+
+            /// `sinh(sqrt(z)) / sqrt(z)`, which is `sin(sqrt(-z)) / sqrt(-z)` for negative `z`
+            pub fn sinhc_sqrt(z: Complex) -> Complex {
+                let root = sqrt(z);
+                if root.0 == 0.0 && root.1 == 0.0 {
+                    return (1.0, 0.0);
+                }
+                div((root.0.sinh() * root.1.cos(), root.0.cosh() * root.1.sin()), root)
+            }
+
+            pub fn inverse_sqrt(z: Complex) -> Complex {
+                div((1.0, 0.0), sqrt(z))
+            }
+
+            pub fn sinhc_sqrt_derivative(x: $scalar) -> $scalar {
+                if x * x < 0.01 {
+                    return series(&SINHC_SQRT_SERIES, (x, 0.0))[1].0;
+                }
+                (cosh_sqrt((x, 0.0)).0 - sinhc_sqrt((x, 0.0)).0) / (2.0 * x)
+            }
+
+            /// `acosh(z) / sqrt(z² - 1)`, which is `acos(z) / sqrt(1 - z²)` for `-1 < z < 1`
+            pub fn acoshc(z: Complex) -> Complex {
+                let u = (z.0 - 1.0, z.1);
+                if u.0 * u.0 + u.1 * u.1 < 0.01 {
+                    return series(&ACOSHC_SERIES, u)[0];
+                }
+                let root = sqrt(add(mul(z, z), (-1.0, 0.0)));
+                div(ln(add(z, root)), root)
+            }
+
+            pub fn acoshc_derivative(x: $scalar) -> $scalar {
+                let u = x - 1.0;
+                if u * u < 0.01 {
+                    return series(&ACOSHC_SERIES, (u, 0.0))[1].0;
+                }
+                (x * acoshc((x, 0.0)).0 - 1.0) / (1.0 - x * x)
+            }
+
+            /// `[f0, f1]` such that `f(a + P) = f0 + f1 * P` where `P * P = square` commutes with `a`
+            ///
+            /// `P` acts like a real (positive `square`), complex (negative `square`) or dual (zero `square`) unit.
+            pub fn split(a: $scalar, square: $scalar, f: impl Fn(Complex) -> Complex, derivative: impl Fn($scalar) -> $scalar) -> [$scalar; 2] {
+                if square > 0.0 {
+                    let root = square.sqrt();
+                    let (plus, minus) = (f((a + root, 0.0)).0, f((a - root, 0.0)).0);
+                    [0.5 * (plus + minus), 0.5 * (plus - minus) / root]
+                } else if square < 0.0 {
+                    let root = (-square).sqrt();
+                    let value = f((a, root));
+                    [value.0, value.1 / root]
+                } else {
+                    [f((a, 0.0)).0, derivative(a)]
+                }
+            }
+        }
 
-    fn transformation(self, other: f32) -> f32 {
-        self.geometric_product(other)
-            .geometric_product(self.reversal())
-    }
+        impl BivectorSplit for $scalar {
+            type Coefficients = simd::$SimdX4;
+
+            fn exp_coefficients(self, wedge_square: $scalar) -> simd::$SimdX4 {
+                let [c0, c1] = $module::split(self, wedge_square, $module::cosh_sqrt, |x| 0.5 * $module::sinhc_sqrt((x, 0.0)).0);
+                let [s0, s1] = $module::split(self, wedge_square, $module::sinhc_sqrt, $module::sinhc_sqrt_derivative);
+                simd::$SimdX4::from([c0, c1, s0, s1])
+            }
+
+            fn ln_coefficients(self, scalar: $scalar, quadvector_square: $scalar) -> simd::$SimdX4 {
+                let [l0, l1] = $module::split(
+                    scalar / self,
+                    quadvector_square / (self * self),
+                    $module::acoshc,
+                    $module::acoshc_derivative,
+                );
+                simd::$SimdX4::from([self.ln(), l0 / self, l1 / (self * self), 0.0])
+            }
+
+            fn inverse_sqrt_coefficients(self, quadvector_square: $scalar) -> simd::$SimdX4 {
+                let [f0, f1] = $module::split(self, quadvector_square, $module::inverse_sqrt, |x| -0.5 / (x * x.sqrt()));
+                simd::$SimdX4::from([f0, f1, 0.0, 0.0])
+            }
+        }
+    };
 }
 
-impl epga1d::ComplexNumber {
-    pub fn real(self) -> f32 {
-        self[0]
-    }
+impl_bivector_split!(f32, Simd32x4, bivector_split_f32);
+impl_bivector_split!(f64, Simd64x4, bivector_split_f64);
 
-    pub fn imaginary(self) -> f32 {
-        self[1]
-    }
+/// Scalars of the batch classes, which hold one scalar per instance
+macro_rules! impl_batch_scalar {
+    ($lanes:ty) => {
+        impl Zero for $lanes {
+            fn zero() -> Self {
+                Self::from(0.0)
+            }
+        }
 
-    pub fn from_polar(magnitude: f32, argument: f32) -> Self {
-        Self::new(magnitude * argument.cos(), magnitude * argument.sin())
-    }
+        impl One for $lanes {
+            fn one() -> Self {
+                Self::from(1.0)
+            }
+        }
 
-    pub fn arg(self) -> f32 {
-        self.imaginary().atan2(self.real())
-    }
-}
+        impl Automorphism for $lanes {
+            type Output = $lanes;
 
-impl Exp for epga1d::ComplexNumber {
-    type Output = Self;
+            fn automorphism(self) -> $lanes {
+                self
+            }
+        }
 
-    fn exp(self) -> Self {
-        Self::from_polar(self[0].exp(), self[1])
-    }
-}
+        impl Reversal for $lanes {
+            type Output = $lanes;
 
-impl Ln for epga1d::ComplexNumber {
-    type Output = Self;
+            fn reversal(self) -> $lanes {
+                self
+            }
+        }
 
-    fn ln(self) -> Self {
-        Self::new(self.magnitude().ln(), self.arg())
-    }
-}
+        impl Conjugation for $lanes {
+            type Output = $lanes;
 
-impl Powf for epga1d::ComplexNumber {
-    type Output = Self;
+            fn conjugation(self) -> $lanes {
+                self
+            }
+        }
 
-    fn powf(self, exponent: f32) -> Self {
-        Self::from_polar(self.magnitude().powf(exponent), self.arg() * exponent)
-    }
-}
+        impl GeometricProduct<$lanes> for $lanes {
+            type Output = $lanes;
 
-impl Exp for ppga2d::IdealPoint {
-    type Output = ppga2d::Translator;
+            fn geometric_product(self, other: $lanes) -> $lanes {
+                self * other
+            }
+        }
 
-    fn exp(self) -> ppga2d::Translator {
-        ppga2d::Translator::new(1.0, self[0], self[1])
-    }
-}
+        impl OuterProduct<$lanes> for $lanes {
+            type Output = $lanes;
 
-impl Ln for ppga2d::Translator {
-    type Output = ppga2d::IdealPoint;
+            fn outer_product(self, other: $lanes) -> $lanes {
+                self * other
+            }
+        }
 
-    fn ln(self) -> ppga2d::IdealPoint {
-        let result: ppga2d::IdealPoint = self.into();
-        result * (1.0 / self[0])
-    }
-}
+        impl InnerProduct<$lanes> for $lanes {
+            type Output = $lanes;
 
-impl Powf for ppga2d::Translator {
-    type Output = Self;
+            fn inner_product(self, other: $lanes) -> $lanes {
+                self * other
+            }
+        }
 
-    fn powf(self, exponent: f32) -> Self {
-        (self.ln() * exponent).exp()
-    }
-}
+        impl LeftContraction<$lanes> for $lanes {
+            type Output = $lanes;
 
-impl Exp for ppga2d::Point {
-    type Output = ppga2d::Motor;
+            fn left_contraction(self, other: $lanes) -> $lanes {
+                self * other
+            }
+        }
+
+        impl RightContraction<$lanes> for $lanes {
+            type Output = $lanes;
 
-    fn exp(self) -> ppga2d::Motor {
-        let det = self[0] * self[0];
-        if det <= 0.0 {
-            return ppga2d::Motor::new(1.0, 0.0, self[1], self[2]);
+            fn right_contraction(self, other: $lanes) -> $lanes {
+                self * other
+            }
         }
-        let a = det.sqrt();
-        let c = a.cos();
-        let s = a.sin() / a;
-        let g0 = simd::Simd32x3::from(s) * self.group0();
-        ppga2d::Motor::new(c, g0[0], g0[1], g0[2])
-    }
-}
 
-impl Ln for ppga2d::Motor {
-    type Output = ppga2d::Point;
+        impl ScalarProduct<$lanes> for $lanes {
+            type Output = $lanes;
 
-    fn ln(self) -> ppga2d::Point {
-        let det = 1.0 - self[0] * self[0];
-        if det <= 0.0 {
-            return ppga2d::Point::new(0.0, self[2], self[3]);
+            fn scalar_product(self, other: $lanes) -> $lanes {
+                self * other
+            }
         }
-        let a = 1.0 / det;
-        let b = self[0].acos() * a.sqrt();
-        let g0 = simd::Simd32x4::from(b) * self.group0();
-        ppga2d::Point::new(g0[1], g0[2], g0[3])
-    }
-}
 
-impl Powf for ppga2d::Motor {
-    type Output = Self;
+        impl SquaredMagnitude for $lanes {
+            type Output = $lanes;
 
-    fn powf(self, exponent: f32) -> Self {
-        (self.ln() * exponent).exp()
-    }
-}
+            fn squared_magnitude(self) -> $lanes {
+                self.scalar_product(self.reversal())
+            }
+        }
 
-impl Exp for ppga3d::IdealPoint {
-    type Output = ppga3d::Translator;
+        impl Magnitude for $lanes {
+            type Output = $lanes;
 
-    fn exp(self) -> ppga3d::Translator {
-        ppga3d::Translator::new(1.0, self[0], self[1], self[2])
-    }
-}
+            fn magnitude(self) -> $lanes {
+                self.squared_magnitude().sqrt()
+            }
+        }
 
-impl Ln for ppga3d::Translator {
-    type Output = ppga3d::IdealPoint;
+        impl Inverse for $lanes {
+            type Output = $lanes;
 
-    fn ln(self) -> ppga3d::IdealPoint {
-        let result: ppga3d::IdealPoint = self.into();
-        result * (1.0 / self[0])
-    }
-}
+            fn inverse(self) -> $lanes {
+                Self::from(1.0) / self
+            }
+        }
 
-impl Powf for ppga3d::Translator {
-    type Output = Self;
+        impl GeometricQuotient<$lanes> for $lanes {
+            type Output = $lanes;
 
-    fn powf(self, exponent: f32) -> Self {
-        (self.ln() * exponent).exp()
-    }
-}
+            fn geometric_quotient(self, other: $lanes) -> $lanes {
+                self.geometric_product(other.inverse())
+            }
+        }
 
-impl Exp for ppga3d::Line {
-    type Output = ppga3d::Motor;
+        impl Transformation<$lanes> for $lanes {
+            type Output = $lanes;
 
-    fn exp(self) -> ppga3d::Motor {
-        let det = self[3] * self[3] + self[4] * self[4] + self[5] * self[5];
-        if det <= 0.0 {
-            return ppga3d::Motor::new(1.0, 0.0, 0.0, 0.0, 0.0, self[0], self[1], self[2]);
+            fn transformation(self, other: $lanes) -> $lanes {
+                self.geometric_product(other)
+                    .geometric_product(self.reversal())
+            }
         }
-        let a = det.sqrt();
-        let c = a.cos();
-        let s = a.sin() / a;
-        let m = self[0] * self[3] + self[1] * self[4] + self[2] * self[5];
-        let t = m / det * (c - s);
-        let g0 = simd::Simd32x3::from(s) * self.group1();
-        let g1 = simd::Simd32x3::from(s) * self.group0() + simd::Simd32x3::from(t) * self.group1();
-        ppga3d::Motor::new(c, g0[0], g0[1], g0[2], s * m, g1[0], g1[1], g1[2])
-    }
+    };
 }
 
-impl Ln for ppga3d::Motor {
-    type Output = ppga3d::Line;
+impl_batch_scalar!(simd::Simd32x4);
+impl_batch_scalar!(simd::Simd64x4);
+
+macro_rules! impl_complex_number {
+    ($algebra:ident, $scalar:ident) => {
+        impl $algebra::ComplexNumber {
+            pub fn real(self) -> $scalar {
+                self[0]
+            }
+
+            pub fn imaginary(self) -> $scalar {
+                self[1]
+            }
 
-    fn ln(self) -> ppga3d::Line {
-        let det = 1.0 - self[0] * self[0];
-        if det <= 0.0 {
-            return ppga3d::Line::new(self[5], self[6], self[7], 0.0, 0.0, 0.0);
+            pub fn from_polar(magnitude: $scalar, argument: $scalar) -> Self {
+                Self::new(magnitude * argument.cos(), magnitude * argument.sin())
+            }
+
+            pub fn arg(self) -> $scalar {
+                self.imaginary().atan2(self.real())
+            }
+        }
+    };
+}
+
+impl_complex_number!(epga1d, f32);
+impl_complex_number!(epga1d_f64, f64);
+
+/// Conversions between Euclidean coordinates and the points, spheres and planes of a conformal algebra, in its scalar type
+macro_rules! impl_conformal {
+    ($algebra:ident, $scalar:ident) => {
+        impl $algebra::Point {
+            /// `(e0 - e4) / 2`
+            pub const fn origin() -> Self {
+                Self::new(0.0, 0.0, 0.0, 0.5, -0.5)
+            }
+
+            /// `e0 + e4`
+            pub const fn infinity() -> Self {
+                Self::new(0.0, 0.0, 0.0, 1.0, 1.0)
+            }
+
+            /// Embeds a Euclidean point (up projection): `x + origin + x² / 2 * infinity`
+            pub fn up(x: [$scalar; 3]) -> Self {
+                let x = Self::new(x[0], x[1], x[2], 0.0, 0.0);
+                x + Self::origin() + Self::infinity().geometric_product(0.5 * x.scalar_product(x))
+            }
+
+            /// `-self · infinity`, which is `1.0` for points created by [up](Self::up)
+            ///
+            /// Not to be confused with [Weight], which splits off the elements without a degenerate generator in other algebras.
+            pub fn normalization_factor(self) -> $scalar {
+                -self.scalar_product(Self::infinity())
+            }
+
+            /// Extracts the Euclidean point (down projection), which also works for points scaled by any factor
+            pub fn down(self) -> [$scalar; 3] {
+                let normalized = self.geometric_product(1.0 / self.normalization_factor());
+                [normalized[0], normalized[1], normalized[2]]
+            }
         }
-        let a = 1.0 / det;
-        let b = self[0].acos() * a.sqrt();
-        let c = a * self[4] * (1.0 - self[0] * b);
-        let g0 = simd::Simd32x4::from(b) * self.group1() + simd::Simd32x4::from(c) * self.group0();
-        let g1 = simd::Simd32x4::from(b) * self.group0();
-        ppga3d::Line::new(g0[1], g0[2], g0[3], g1[1], g1[2], g1[3])
-    }
-}
 
-impl Powf for ppga3d::Motor {
-    type Output = Self;
+        impl $algebra::Sphere {
+            /// Sphere around `center`, the outer product of four points on it (up to scale)
+            pub fn from_center_radius(center: [$scalar; 3], radius: $scalar) -> Self {
+                Self::from_dual_point($algebra::Point::up(center) - $algebra::Point::infinity().geometric_product(0.5 * radius * radius))
+            }
+
+            /// Inverse of [dual_point](Self::dual_point)
+            pub fn from_dual_point(point: $algebra::Point) -> Self {
+                $algebra::Point::new(point[0], point[1], point[2], -point[3], point[4]).dual()
+            }
+
+            /// Vector `up(center) - radius² / 2 * infinity` (up to scale), which has a scalar product of `0.0` with all points on the sphere
+            ///
+            /// Unlike [Dual], this takes the negative square of e0 into account.
+            pub fn dual_point(self) -> $algebra::Point {
+                let dual = self.dual();
+                $algebra::Point::new(dual[0], dual[1], dual[2], -dual[3], dual[4])
+            }
+
+            pub fn center(self) -> [$scalar; 3] {
+                self.dual_point().down()
+            }
+
+            /// Negative for imaginary spheres
+            pub fn squared_radius(self) -> $scalar {
+                let dual = self.dual_point();
+                dual.scalar_product(dual) / (dual.normalization_factor() * dual.normalization_factor())
+            }
+        }
 
-    fn powf(self, exponent: f32) -> Self {
-        (self.ln() * exponent).exp()
-    }
+        impl $algebra::Plane {
+            /// Plane with the unit `normal` at the signed `distance` from the origin, the outer product of three points on it and infinity (up to scale)
+            pub fn from_normal_distance(normal: [$scalar; 3], distance: $scalar) -> Self {
+                $algebra::Sphere::from_dual_point(
+                    $algebra::Point::new(normal[0], normal[1], normal[2], 0.0, 0.0) + $algebra::Point::infinity().geometric_product(distance),
+                )
+                .into()
+            }
+        }
+    };
 }
 
+impl_conformal!(cga3d, f32);
+
 /// All elements set to `0.0`
 pub trait Zero {
     fn zero() -> Self;
@@ -334,6 +700,22 @@ pub trait Dual {
     fn dual(self) -> Self::Output;
 }
 
+/// Elements which contain a degenerate generator (e.g. e0 in PGA), the others are set to zero
+///
+/// This is the ideal part, e.g. the direction of a point at infinity or the translation of a motor.
+pub trait Bulk {
+    type Output;
+    fn bulk(self) -> Self::Output;
+}
+
+/// Elements which contain no degenerate generator, the others are set to zero
+///
+/// E.g. the homogeneous coordinate of a point, which is zero for points at infinity.
+pub trait Weight {
+    type Output;
+    fn weight(self) -> Self::Output;
+}
+
 /// Negates elements with `grade % 2 == 1`
 ///
 /// Also called main involution
@@ -432,6 +814,30 @@ pub trait Magnitude {
     fn magnitude(self) -> Self::Output;
 }
 
+/// Length of the [Bulk], measured as if the degenerate generators squared to one
+///
+/// Unlike the [Magnitude], this is not zero for ideal elements, e.g. the distance of a normalized point from the origin in PGA.
+pub trait BulkNorm {
+    type Output;
+    fn bulk_norm(self) -> Self::Output;
+}
+
+/// Length of the [Weight], which is the [Magnitude] in degenerate metrics
+///
+/// Dividing a homogeneous point by it normalizes the point.
+pub trait WeightNorm {
+    type Output;
+    fn weight_norm(self) -> Self::Output;
+}
+
+/// Same as the [BulkNorm]
+///
+/// Also called infinity norm
+pub trait IdealNorm {
+    type Output;
+    fn ideal_norm(self) -> Self::Output;
+}
+
 /// Direction without magnitude (set to scalar `-1.0` or `1.0`)
 ///
 /// Also called sign or normalize
@@ -465,7 +871,61 @@ pub trait Powi {
 }
 
 /// Raises a number to an floating point scalar power
-pub trait Powf {
+pub trait Powf<T = f32> {
+    type Output;
+    fn powf(self, exponent: T) -> Self::Output;
+}
+
+/// Normalizes a rotor or motor so that `self * ~self = 1`
+///
+/// Unlike [Signum] this also corrects the quadvector part of `self * ~self`, e.g. the e0123 part of motors in 3D PGA (the study condition).
+/// In five dimensions that correction can leave the class of `self`, so e.g. a `Motor` of `cga3d` is normalized to a `Versor`.
+pub trait Normalize {
     type Output;
-    fn powf(self, exponent: f32) -> Self::Output;
+    fn normalize(self) -> Self::Output;
+}
+
+/// Square root of a rotor or motor, the transformation halfway between the identity and `self`
+///
+/// Apart from a positive factor, whose square root is taken as well (e.g. the magnitude of a complex number), `self` has to be normalized.
+pub trait Sqrt {
+    type Output;
+    fn sqrt(self) -> Self::Output;
+}
+
+/// Closed-form coefficients of the exponential, the logarithm and the square root, which the generated `Exp`, `Ln`, `Sqrt` and `Normalize` are composed of
+///
+/// The square of a bivector `B * B = inner + wedge` splits into a scalar `inner` and a quadvector `wedge`,
+/// which commutes with `B` and squares to the scalar `wedge_square` in up to five dimensions.
+/// Thus every function of `B * B` has the form `f0 + f1 * wedge`, also for bivectors which are not simple.
+pub trait BivectorSplit {
+    type Coefficients;
+
+    /// `[c0, c1, s0, s1]` such that `exp(B) = c0 + c1 * wedge + (s0 + s1 * wedge) * B` where `self` is `inner`
+    fn exp_coefficients(self, wedge_square: Self) -> Self::Coefficients;
+
+    /// `[ln(m), l0 / m, l1 / m², 0]` such that `ln(R) = ln(m) + (l0 / m + l1 / m² * Q) * B` where `self` is the magnitude `m`
+    ///
+    /// The rotor `R = scalar + B + Q` consists of a `scalar`, a bivector `B` and a quadvector `Q` which squares to `quadvector_square`.
+    fn ln_coefficients(self, scalar: Self, quadvector_square: Self) -> Self::Coefficients;
+
+    /// `[f0, f1, 0, 0]` such that `1 / sqrt(self + Q) = f0 + f1 * Q` for a quadvector `Q` which squares to `quadvector_square`
+    fn inverse_sqrt_coefficients(self, quadvector_square: Self) -> Self::Coefficients;
+}
+
+/// Elementary functions of the scalar types
+///
+/// These are inherent methods with the `std` feature, and this trait provides them from `libm` in `#![no_std]`.
+pub trait Float: Sized {
+    fn sqrt(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn acos(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn sinh(self) -> Self;
+    fn cosh(self) -> Self;
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn powf(self, exponent: Self) -> Self;
+    fn powi(self, exponent: i32) -> Self;
 }
@@ -1,6 +1,9 @@
 //! Solves polynomials with real valued coefficients up to degree 4
 
 #![allow(clippy::many_single_char_names)]
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)] // Unused if std is linked anyway, as in tests
+use crate::Float;
 use crate::{epga1d::*, GeometricProduct, GeometricQuotient, Powf, Reversal, SquaredMagnitude};
 
 /// Represents a complex root as homogeneous coordinates
@@ -22,34 +25,74 @@ impl Root {
     }
 }
 
+/// Roots of a polynomial, stored inline as there are at most four
+///
+/// Dereferences to a slice of the roots which were found.
+#[derive(Debug, Clone, Copy)]
+pub struct Roots {
+    roots: [Root; 4],
+    len: usize,
+}
+
+impl Roots {
+    const EMPTY: Self = Self {
+        roots: [Root {
+            numerator: ComplexNumber::new(0.0, 0.0),
+            denominator: 0.0,
+        }; 4],
+        len: 0,
+    };
+
+    fn push(&mut self, root: Root) {
+        self.roots[self.len] = root;
+        self.len += 1;
+    }
+}
+
+impl core::ops::Deref for Roots {
+    type Target = [Root];
+
+    fn deref(&self) -> &[Root] {
+        &self.roots[0..self.len]
+    }
+}
+
+impl IntoIterator for Roots {
+    type Item = Root;
+    type IntoIter = core::iter::Take<core::array::IntoIter<Root, 4>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIterator::into_iter(self.roots).take(self.len)
+    }
+}
+
 /// Finds the discriminant and root of a degree 1 polynomial.
 ///
 /// `0 = coefficients[1] * x + coefficients[0]`
-pub fn solve_linear(coefficients: [f32; 2], error_margin: f32) -> (f32, Vec<Root>) {
+pub fn solve_linear(coefficients: [f32; 2], error_margin: f32) -> (f32, Roots) {
+    let mut solutions = Roots::EMPTY;
     if coefficients[1].abs() <= error_margin {
-        (0.0, vec![])
+        (0.0, solutions)
     } else {
-        (
-            1.0,
-            vec![Root {
-                numerator: ComplexNumber::new(-coefficients[0], 0.0),
-                denominator: coefficients[1],
-            }],
-        )
+        solutions.push(Root {
+            numerator: ComplexNumber::new(-coefficients[0], 0.0),
+            denominator: coefficients[1],
+        });
+        (1.0, solutions)
     }
 }
 
 /// Finds the discriminant and roots of a degree 2 polynomial.
 ///
 /// `0 = coefficients[2] * x.powi(2) + coefficients[1] * x + coefficients[0]`
-pub fn solve_quadratic(coefficients: [f32; 3], error_margin: f32) -> (f32, Vec<Root>) {
+pub fn solve_quadratic(coefficients: [f32; 3], error_margin: f32) -> (f32, Roots) {
     if coefficients[2].abs() <= error_margin {
         return solve_linear([coefficients[0], coefficients[1]], error_margin);
     }
     // https://en.wikipedia.org/wiki/Quadratic_formula
     let discriminant = coefficients[1].powi(2) - 4.0 * coefficients[2] * coefficients[0];
     let q = discriminant.sqrt();
-    let mut solutions = Vec::with_capacity(3);
+    let mut solutions = Roots::EMPTY;
     for s in [-q, q] {
         let numerator = s - ComplexNumber::new(coefficients[1], 0.0);
         solutions.push(Root {
@@ -62,8 +105,8 @@ pub fn solve_quadratic(coefficients: [f32; 3], error_margin: f32) -> (f32, Vec<R
 
 const ROOTS_OF_UNITY_3: [ComplexNumber; 3] = [
     // 0.8660254037844386467637231707529361834714026269051903140279034897
-    // ComplexNumber::from_polar(1.0, -120.0/180.0*std::f32::consts::PI),
-    // ComplexNumber::from_polar(1.0, 120.0/180.0*std::f32::consts::PI),
+    // ComplexNumber::from_polar(1.0, -120.0/180.0*core::f32::consts::PI),
+    // ComplexNumber::from_polar(1.0, 120.0/180.0*core::f32::consts::PI),
     // ComplexNumber::from_polar(1.0, 0.0),
     ComplexNumber::new(-0.5, -0.8660254),
     ComplexNumber::new(-0.5, 0.8660254),
@@ -75,7 +118,7 @@ const ROOTS_OF_UNITY_3: [ComplexNumber; 3] = [
 /// `0 = coefficients[3] * x.powi(3) + coefficients[2] * x.powi(2) + coefficients[1] * x + coefficients[0]`
 ///
 /// Also returns the index of the real root if there are two complex roots and one real root.
-pub fn solve_cubic(coefficients: [f32; 4], error_margin: f32) -> (f32, Vec<Root>, usize) {
+pub fn solve_cubic(coefficients: [f32; 4], error_margin: f32) -> (f32, Roots, usize) {
     if coefficients[3].abs() <= error_margin {
         let (discriminant, roots) = solve_quadratic(
             [coefficients[0], coefficients[1], coefficients[2]],
@@ -89,7 +132,7 @@ pub fn solve_cubic(coefficients: [f32; 4], error_margin: f32) -> (f32, Vec<Root>
         2.0 * coefficients[2].powi(3) - 9.0 * coefficients[3] * coefficients[2] * coefficients[1]
             + 27.0 * coefficients[3].powi(2) * coefficients[0],
     ];
-    let mut solutions = Vec::with_capacity(3);
+    let mut solutions = Roots::EMPTY;
     let discriminant = d[1].powi(2) - 4.0 * d[0].powi(3);
     let c = discriminant.sqrt();
     let c = ((c + ComplexNumber::new(if c + d[1] == 0.0 { -d[1] } else { d[1] }, 0.0)) * 0.5)
@@ -106,14 +149,14 @@ pub fn solve_cubic(coefficients: [f32; 4], error_margin: f32) -> (f32, Vec<Root>
         });
     }
     let real_root =
-        (((std::f32::consts::PI - c.arg()) / (std::f32::consts::PI * 2.0 / 3.0)) as usize + 1) % 3;
+        (((core::f32::consts::PI - c.arg()) / (core::f32::consts::PI * 2.0 / 3.0)) as usize + 1) % 3;
     (discriminant, solutions, real_root)
 }
 
 /// Finds the discriminant and roots of a degree 4 polynomial.
 ///
 /// `0 = coefficients[4] * x.powi(4) + coefficients[3] * x.powi(3) + coefficients[2] * x.powi(2) + coefficients[1] * x + coefficients[0]`
-pub fn solve_quartic(coefficients: [f32; 5], error_margin: f32) -> (f32, Vec<Root>) {
+pub fn solve_quartic(coefficients: [f32; 5], error_margin: f32) -> (f32, Roots) {
     if coefficients[4].abs() <= error_margin {
         let (discriminant, roots, _real_root) = solve_cubic(
             [
@@ -149,7 +192,7 @@ pub fn solve_quartic(coefficients: [f32; 5], error_margin: f32) -> (f32, Vec<Roo
         - ComplexNumber::new(p * 2.0 / 3.0, 0.0))
     .powf(0.5)
         * 0.5;
-    let mut solutions = Vec::with_capacity(4);
+    let mut solutions = Roots::EMPTY;
     for i in 0..4 {
         let f = (e.geometric_product(e) * -4.0 - ComplexNumber::new(2.0 * p, 0.0)
             + ComplexNumber::new(if i & 2 == 0 { q } else { -q }, 0.0).geometric_quotient(e))
@@ -0,0 +1,115 @@
+//! (De)serialization of the generated classes, enabled by the `serde` feature
+//!
+//! Human-readable formats (like JSON) store a class as a map keyed by the basis element names (`{"1": 1.0, "e12": 0.0}`),
+//! all other formats store it as a flat array of its elements.
+//! Deserialization of human-readable formats accepts both.
+
+pub use serde;
+use serde::{
+    de::{self, Deserialize, DeserializeSeed, Deserializer},
+    ser::{Serialize, SerializeMap, SerializeTuple, Serializer},
+};
+
+/// Serializes the elements of a class, see the [module](self) documentation
+pub fn serialize_elements<S: Serializer, T: Serialize>(elements: &[T], names: &[&str], serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        let mut map = serializer.serialize_map(Some(elements.len()))?;
+        for (name, element) in names.iter().zip(elements.iter()) {
+            map.serialize_entry(name, element)?;
+        }
+        map.end()
+    } else {
+        let mut tuple = serializer.serialize_tuple(elements.len())?;
+        for element in elements.iter() {
+            tuple.serialize_element(element)?;
+        }
+        tuple.end()
+    }
+}
+
+/// Deserializes the elements of a class, see the [module](self) documentation
+pub fn deserialize_elements<'de, D: Deserializer<'de>, T: Deserialize<'de> + Copy + Default, const N: usize>(
+    names: &'static [&'static str; N],
+    deserializer: D,
+) -> Result<[T; N], D::Error> {
+    let visitor = ElementsVisitor {
+        names,
+        marker: core::marker::PhantomData,
+    };
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(visitor)
+    } else {
+        deserializer.deserialize_tuple(N, visitor)
+    }
+}
+
+struct ElementsVisitor<T, const N: usize> {
+    names: &'static [&'static str; N],
+    marker: core::marker::PhantomData<T>,
+}
+
+impl<'de, T: Deserialize<'de> + Copy + Default, const N: usize> de::Visitor<'de> for ElementsVisitor<T, N> {
+    type Value = [T; N];
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(formatter, "an array of {} elements or a map of the basis elements ", N)?;
+        for (index, name) in self.names.iter().enumerate() {
+            write!(formatter, "{}{}", if index == 0 { "" } else { ", " }, name)?;
+        }
+        Ok(())
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut elements = [T::default(); N];
+        for (index, element) in elements.iter_mut().enumerate() {
+            *element = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(index, &self))?;
+        }
+        if seq.next_element::<de::IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(N + 1, &self));
+        }
+        Ok(elements)
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut elements = [None; N];
+        while let Some(index) = map.next_key_seed(ElementIndex { names: self.names })? {
+            if elements[index].is_some() {
+                return Err(de::Error::duplicate_field(self.names[index]));
+            }
+            elements[index] = Some(map.next_value()?);
+        }
+        let mut result = [T::default(); N];
+        for (index, element) in result.iter_mut().enumerate() {
+            *element = elements[index].ok_or_else(|| de::Error::missing_field(self.names[index]))?;
+        }
+        Ok(result)
+    }
+}
+
+/// Looks up the index of a basis element name without allocating the key
+struct ElementIndex {
+    names: &'static [&'static str],
+}
+
+impl<'de> DeserializeSeed<'de> for ElementIndex {
+    type Value = usize;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<usize, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de> de::Visitor<'de> for ElementIndex {
+    type Value = usize;
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(formatter, "the name of a basis element")
+    }
+
+    fn visit_str<E: de::Error>(self, name: &str) -> Result<usize, E> {
+        self.names
+            .iter()
+            .position(|other| *other == name)
+            .ok_or_else(|| de::Error::unknown_field(name, self.names))
+    }
+}
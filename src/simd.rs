@@ -1,13 +1,18 @@
 #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
-pub use std::arch::aarch64::*;
+pub use core::arch::aarch64::*;
 #[cfg(all(target_arch = "arm", target_feature = "neon"))]
-pub use std::arch::arm::*;
+pub use core::arch::arm::*;
 #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
-pub use std::arch::wasm32::*;
+pub use core::arch::wasm32::*;
 #[cfg(all(target_arch = "x86", target_feature = "sse2"))]
-pub use std::arch::x86::*;
+pub use core::arch::x86::*;
 #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
-pub use std::arch::x86_64::*;
+pub use core::arch::x86_64::*;
+#[cfg(feature = "portable_simd")]
+pub use std::simd::StdFloat;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)] // Only the fallbacks use it
+use crate::Float;
 
 #[derive(Clone, Copy)]
 #[repr(C)]
@@ -30,34 +35,137 @@ pub union Simd32x4 {
     #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
     pub v128: v128,
 
+    // Portable
+    #[cfg(feature = "portable_simd")]
+    pub portable: std::simd::f32x4,
+
     // Fallback
     pub f32x4: [f32; 4],
     pub i32x4: [i32; 4],
     pub u32x4: [u32; 4],
 }
 
+/// Three lanes of [f32] in a [Simd32x4]
+///
+/// The unused lane is always initialized (to zero by the constructors), so that classes containing it can be `bytemuck::Pod`.
+/// Therefore, the fallback arrays of fewer lanes are private and it can only be constructed through `v32x4` or `From`.
 #[derive(Clone, Copy)]
 #[repr(C)]
 pub union Simd32x3 {
     pub v32x4: Simd32x4,
 
     // Fallback
-    pub f32x3: [f32; 3],
-    pub i32x3: [i32; 3],
-    pub u32x3: [u32; 3],
+    f32x3: [f32; 3],
+    i32x3: [i32; 3],
+    u32x3: [u32; 3],
 }
 
+/// Two lanes of [f32] in a [Simd32x4], see [Simd32x3] for the unused lanes
 #[derive(Clone, Copy)]
 #[repr(C)]
 pub union Simd32x2 {
     pub v32x4: Simd32x4,
 
     // Fallback
-    pub f32x2: [f32; 2],
-    pub i32x2: [i32; 2],
-    pub u32x2: [u32; 2],
+    f32x2: [f32; 2],
+    i32x2: [i32; 2],
+    u32x2: [u32; 2],
+}
+
+/// Four lanes of [f64], which fill a 256 bit AVX register
+///
+/// Without AVX a pair of 128 bit registers is used instead, the alignment is 32 bytes either way, so that the layout does not depend on the target features.
+#[derive(Clone, Copy)]
+#[repr(C, align(32))]
+pub union Simd64x4 {
+    // Intel / AMD
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "avx"))]
+    pub f256: __m256d,
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"))]
+    pub f128: [__m128d; 2],
+
+    // ARM
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    pub f128: [float64x2_t; 2],
+
+    // Web
+    #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+    pub v128: [v128; 2],
+
+    // Fallback
+    pub f64x4: [f64; 4],
+    pub i64x4: [i64; 4],
+    pub u64x4: [u64; 4],
+}
+
+/// Three lanes of [f64] in a [Simd64x4], see [Simd32x3] for the unused lanes
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub union Simd64x3 {
+    pub v64x4: Simd64x4,
+
+    // Fallback
+    f64x3: [f64; 3],
+    i64x3: [i64; 3],
+    u64x3: [u64; 3],
+}
+
+/// Two lanes of [f64] in a [Simd64x4], see [Simd32x3] for the unused lanes
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub union Simd64x2 {
+    pub v64x4: Simd64x4,
+
+    // Fallback
+    f64x2: [f64; 2],
+    i64x2: [i64; 2],
+    u64x2: [u64; 2],
+}
+
+/// Eight lanes of [f32], which fill a 256 bit AVX register
+///
+/// Without AVX a pair of [Simd32x4] is used instead, the alignment is 32 bytes either way, so that the layout does not depend on the target features.
+#[derive(Clone, Copy)]
+#[repr(C, align(32))]
+pub union Simd32x8 {
+    // Intel / AMD
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "avx"))]
+    pub f256: __m256,
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "avx"))]
+    pub i256: __m256i,
+
+    // Other architectures
+    pub v32x4: [Simd32x4; 2],
+
+    // Fallback
+    pub f32x8: [f32; 8],
+    pub i32x8: [i32; 8],
+    pub u32x8: [u32; 8],
 }
 
+/// Eight lanes of [f64], which fill a 512 bit AVX-512 register
+///
+/// Without AVX-512 a pair of [Simd64x4] is used instead, the alignment is 64 bytes either way, so that the layout does not depend on the target features.
+#[derive(Clone, Copy)]
+#[repr(C, align(64))]
+pub union Simd64x8 {
+    // Intel / AMD
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "avx512f"))]
+    pub f512: __m512d,
+
+    // Other architectures
+    pub v64x4: [Simd64x4; 2],
+
+    // Fallback
+    pub f64x8: [f64; 8],
+    pub i64x8: [i64; 8],
+    pub u64x8: [u64; 8],
+}
+
+/// Selects the implementation for the SIMD extension of the target architecture
+///
+/// The first form is for types which delegate to [Simd32x4] if it is native, the second has one arm per extension.
+#[cfg(not(feature = "portable_simd"))]
 #[macro_export]
 macro_rules! match_architecture {
     ($Simd:ident, $native:tt, $fallback:tt,) => {{
@@ -74,7 +182,7 @@ macro_rules! match_architecture {
         )))]
         unsafe { $Simd $fallback }
     }};
-    ($Simd:ident, $x86:tt, $arm:tt, $web:tt, $fallback:tt,) => {{
+    ($Simd:ident, $portable:tt, $x86:tt, $arm:tt, $web:tt, $fallback:tt,) => {{
         #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"))]
         unsafe { $Simd $x86 }
         #[cfg(all(any(target_arch = "arm", target_arch = "aarch64"), target_feature = "neon"))]
@@ -90,11 +198,90 @@ macro_rules! match_architecture {
     }};
 }
 
+/// Like [match_architecture] but with the `portable_simd` feature, which selects the `core::simd` implementation on every target
+#[cfg(feature = "portable_simd")]
+#[macro_export]
+macro_rules! match_architecture {
+    ($Simd:ident, $native:tt, $fallback:tt,) => {{
+        $Simd $native
+    }};
+    ($Simd:ident, $portable:tt, $x86:tt, $arm:tt, $web:tt, $fallback:tt,) => {{
+        unsafe { $Simd $portable }
+    }};
+}
+
+/// Like [match_architecture] but for 64 bit lanes, which have no NEON support on 32 bit ARM
+#[macro_export]
+macro_rules! match_architecture_f64 {
+    ($Simd:ident, $avx:tt, $sse2:tt, $neon:tt, $web:tt, $fallback:tt,) => {{
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "avx"))]
+        unsafe { $Simd $avx }
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2", not(target_feature = "avx")))]
+        unsafe { $Simd $sse2 }
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        unsafe { $Simd $neon }
+        #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+        unsafe { $Simd $web }
+        #[cfg(not(any(
+            all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"),
+            all(target_arch = "aarch64", target_feature = "neon"),
+            all(target_arch = "wasm32", target_feature = "simd128"),
+        )))]
+        unsafe { $Simd $fallback }
+    }};
+}
+
+/// Like [match_architecture] but for [Simd32x8], which uses AVX if enabled and otherwise a pair of [Simd32x4]
+#[macro_export]
+macro_rules! match_architecture_avx {
+    ($Simd:ident, $avx:tt, $fallback:tt,) => {{
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "avx"))]
+        unsafe { $Simd $avx }
+        #[cfg(not(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "avx")))]
+        unsafe { $Simd $fallback }
+    }};
+}
+
+/// Like [match_architecture] but for [Simd64x8], which uses AVX-512 if enabled and otherwise a pair of [Simd64x4]
+#[macro_export]
+macro_rules! match_architecture_avx512 {
+    ($Simd:ident, $avx512:tt, $fallback:tt,) => {{
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "avx512f"))]
+        unsafe { $Simd $avx512 }
+        #[cfg(not(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "avx512f")))]
+        unsafe { $Simd $fallback }
+    }};
+}
+
 #[macro_export]
 macro_rules! swizzle {
+    ($self:expr, $a:literal, $b:literal, $c:literal, $d:literal, $e:literal, $f:literal, $g:literal, $h:literal) => {{
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "avx2"))]
+        unsafe {
+            $crate::simd::Simd32x8 {
+                f256: $crate::simd::_mm256_permutevar8x32_ps($self.f256, $crate::simd::_mm256_setr_epi32($a, $b, $c, $d, $e, $f, $g, $h)),
+            }
+        }
+        #[cfg(not(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "avx2")))]
+        unsafe {
+            $crate::simd::Simd32x8 {
+                f32x8: [
+                    $self.f32x8[$a],
+                    $self.f32x8[$b],
+                    $self.f32x8[$c],
+                    $self.f32x8[$d],
+                    $self.f32x8[$e],
+                    $self.f32x8[$f],
+                    $self.f32x8[$g],
+                    $self.f32x8[$h],
+                ],
+            }
+        }
+    }};
     ($self:expr, $x:literal, $y:literal, $z:literal, $w:literal) => {
         $crate::match_architecture!(
             Simd32x4,
+            { portable: $self.swizzle::<$x, $y, $z, $w>().portable },
             { f128: $crate::simd::_mm_permute_ps($self.f128, ($x as i32) | (($y as i32) << 2) | (($z as i32) << 4) | (($w as i32) << 6)) },
             { f32x4: [
                 $self.f32x4[$x],
@@ -115,104 +302,164 @@ macro_rules! swizzle {
         $crate::match_architecture!(
             Simd32x3,
             { v32x4: $crate::swizzle!($self.v32x4, $x, $y, $z, 0) },
-            { f32x3: [
-                $self.f32x3[$x],
-                $self.f32x3[$y],
-                $self.f32x3[$z],
-            ] },
+            { v32x4: $crate::simd::Simd32x4::from([
+                $self[$x],
+                $self[$y],
+                $self[$z],
+                0.0,
+            ]) },
         )
     };
     ($self:expr, $x:literal, $y:literal) => {
         $crate::match_architecture!(
             Simd32x2,
             { v32x4: $crate::swizzle!($self.v32x4, $x, $y, 0, 0) },
-            { f32x2: [
-                $self.f32x2[$x],
-                $self.f32x2[$y],
-            ] },
+            { v32x4: $crate::simd::Simd32x4::from([
+                $self[$x],
+                $self[$y],
+                0.0,
+                0.0,
+            ]) },
         )
     };
 }
 
-impl std::ops::Index<usize> for Simd32x4 {
+/// Like [swizzle] but for [Simd64x8], [Simd64x4], [Simd64x3] and [Simd64x2]
+///
+/// Lanes are shuffled through the fallback arrays, as there is no cheap cross lane permutation of doubles before AVX2.
+#[macro_export]
+macro_rules! swizzle64 {
+    ($self:expr, $a:literal, $b:literal, $c:literal, $d:literal, $e:literal, $f:literal, $g:literal, $h:literal) => {
+        unsafe {
+            $crate::simd::Simd64x8 {
+                f64x8: [
+                    $self.f64x8[$a],
+                    $self.f64x8[$b],
+                    $self.f64x8[$c],
+                    $self.f64x8[$d],
+                    $self.f64x8[$e],
+                    $self.f64x8[$f],
+                    $self.f64x8[$g],
+                    $self.f64x8[$h],
+                ],
+            }
+        }
+    };
+    ($self:expr, $x:literal, $y:literal, $z:literal, $w:literal) => {
+        unsafe {
+            $crate::simd::Simd64x4 {
+                f64x4: [$self.f64x4[$x], $self.f64x4[$y], $self.f64x4[$z], $self.f64x4[$w]],
+            }
+        }
+    };
+    ($self:expr, $x:literal, $y:literal, $z:literal) => {
+        $crate::simd::Simd64x3 {
+            v64x4: $crate::simd::Simd64x4::from([$self[$x], $self[$y], $self[$z], 0.0]),
+        }
+    };
+    ($self:expr, $x:literal, $y:literal) => {
+        $crate::simd::Simd64x2 {
+            v64x4: $crate::simd::Simd64x4::from([$self[$x], $self[$y], 0.0, 0.0]),
+        }
+    };
+}
+
+impl core::ops::Index<usize> for Simd32x4 {
     type Output = f32;
 
+    #[inline(always)]
     fn index(&self, index: usize) -> &Self::Output {
         unsafe { &self.f32x4[index] }
     }
 }
 
-impl std::ops::Index<usize> for Simd32x3 {
+impl core::ops::Index<usize> for Simd32x3 {
     type Output = f32;
 
+    #[inline(always)]
     fn index(&self, index: usize) -> &Self::Output {
         unsafe { &self.f32x3[index] }
     }
 }
 
-impl std::ops::Index<usize> for Simd32x2 {
+impl core::ops::Index<usize> for Simd32x2 {
     type Output = f32;
 
+    #[inline(always)]
     fn index(&self, index: usize) -> &Self::Output {
         unsafe { &self.f32x2[index] }
     }
 }
 
-impl std::ops::IndexMut<usize> for Simd32x4 {
+impl core::ops::IndexMut<usize> for Simd32x4 {
+    #[inline(always)]
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         unsafe { &mut self.f32x4[index] }
     }
 }
 
-impl std::ops::IndexMut<usize> for Simd32x3 {
+impl core::ops::IndexMut<usize> for Simd32x3 {
+    #[inline(always)]
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         unsafe { &mut self.f32x3[index] }
     }
 }
 
-impl std::ops::IndexMut<usize> for Simd32x2 {
+impl core::ops::IndexMut<usize> for Simd32x2 {
+    #[inline(always)]
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         unsafe { &mut self.f32x2[index] }
     }
 }
 
-impl std::convert::From<Simd32x4> for [f32; 4] {
+impl core::convert::From<Simd32x4> for [f32; 4] {
+    #[inline(always)]
     fn from(simd: Simd32x4) -> Self {
         unsafe { simd.f32x4 }
     }
 }
 
-impl std::convert::From<Simd32x3> for [f32; 3] {
+impl core::convert::From<Simd32x3> for [f32; 3] {
+    #[inline(always)]
     fn from(simd: Simd32x3) -> Self {
         unsafe { simd.f32x3 }
     }
 }
 
-impl std::convert::From<Simd32x2> for [f32; 2] {
+impl core::convert::From<Simd32x2> for [f32; 2] {
+    #[inline(always)]
     fn from(simd: Simd32x2) -> Self {
         unsafe { simd.f32x2 }
     }
 }
 
-impl std::convert::From<[f32; 4]> for Simd32x4 {
+impl core::convert::From<[f32; 4]> for Simd32x4 {
+    #[inline(always)]
     fn from(f32x4: [f32; 4]) -> Self {
         Self { f32x4 }
     }
 }
 
-impl std::convert::From<[f32; 3]> for Simd32x3 {
+impl core::convert::From<[f32; 3]> for Simd32x3 {
+    #[inline(always)]
     fn from(f32x3: [f32; 3]) -> Self {
-        Self { f32x3 }
+        Self {
+            v32x4: Simd32x4::from([f32x3[0], f32x3[1], f32x3[2], 0.0]),
+        }
     }
 }
 
-impl std::convert::From<[f32; 2]> for Simd32x2 {
+impl core::convert::From<[f32; 2]> for Simd32x2 {
+    #[inline(always)]
     fn from(f32x2: [f32; 2]) -> Self {
-        Self { f32x2 }
+        Self {
+            v32x4: Simd32x4::from([f32x2[0], f32x2[1], 0.0, 0.0]),
+        }
     }
 }
 
-impl std::convert::From<f32> for Simd32x4 {
+impl core::convert::From<f32> for Simd32x4 {
+    #[inline(always)]
     fn from(value: f32) -> Self {
         Self {
             f32x4: [value, value, value, value],
@@ -220,24 +467,26 @@ impl std::convert::From<f32> for Simd32x4 {
     }
 }
 
-impl std::convert::From<f32> for Simd32x3 {
+impl core::convert::From<f32> for Simd32x3 {
+    #[inline(always)]
     fn from(value: f32) -> Self {
         Self {
-            f32x3: [value, value, value],
+            v32x4: Simd32x4::from([value, value, value, 0.0]),
         }
     }
 }
 
-impl std::convert::From<f32> for Simd32x2 {
+impl core::convert::From<f32> for Simd32x2 {
+    #[inline(always)]
     fn from(value: f32) -> Self {
         Self {
-            f32x2: [value, value],
+            v32x4: Simd32x4::from([value, value, 0.0, 0.0]),
         }
     }
 }
 
-impl std::fmt::Debug for Simd32x4 {
-    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Debug for Simd32x4 {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
         formatter
             .debug_list()
             .entries([self[0], self[1], self[2], self[3]].iter())
@@ -245,8 +494,8 @@ impl std::fmt::Debug for Simd32x4 {
     }
 }
 
-impl std::fmt::Debug for Simd32x3 {
-    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Debug for Simd32x3 {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
         formatter
             .debug_list()
             .entries([self[0], self[1], self[2]].iter())
@@ -254,8 +503,8 @@ impl std::fmt::Debug for Simd32x3 {
     }
 }
 
-impl std::fmt::Debug for Simd32x2 {
-    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Debug for Simd32x2 {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
         formatter
             .debug_list()
             .entries([self[0], self[1]].iter())
@@ -263,12 +512,14 @@ impl std::fmt::Debug for Simd32x2 {
     }
 }
 
-impl std::ops::Add<Simd32x4> for Simd32x4 {
+impl core::ops::Add<Simd32x4> for Simd32x4 {
     type Output = Simd32x4;
 
+    #[inline(always)]
     fn add(self, other: Self) -> Self {
         match_architecture!(
             Self,
+            { portable: self.portable + other.portable },
             { f128: _mm_add_ps(self.f128, other.f128) },
             { f128: vaddq_f32(self.f128, other.f128) },
             { v128: f32x4_add(self.v128, other.v128) },
@@ -282,43 +533,50 @@ impl std::ops::Add<Simd32x4> for Simd32x4 {
     }
 }
 
-impl std::ops::Add<Simd32x3> for Simd32x3 {
+impl core::ops::Add<Simd32x3> for Simd32x3 {
     type Output = Simd32x3;
 
+    #[inline(always)]
     fn add(self, other: Self) -> Self {
         match_architecture!(
             Self,
             { v32x4: unsafe { self.v32x4 + other.v32x4 } },
-            { f32x3: [
+            { v32x4: Simd32x4::from([
                 self.f32x3[0] + other.f32x3[0],
                 self.f32x3[1] + other.f32x3[1],
                 self.f32x3[2] + other.f32x3[2],
-            ] },
+                0.0,
+            ]) },
         )
     }
 }
 
-impl std::ops::Add<Simd32x2> for Simd32x2 {
+impl core::ops::Add<Simd32x2> for Simd32x2 {
     type Output = Simd32x2;
 
+    #[inline(always)]
     fn add(self, other: Self) -> Self {
         match_architecture!(
             Self,
             { v32x4: unsafe { self.v32x4 + other.v32x4 } },
-            { f32x2: [
+            { v32x4: Simd32x4::from([
                 self.f32x2[0] + other.f32x2[0],
                 self.f32x2[1] + other.f32x2[1],
-            ] },
+                0.0,
+                0.0,
+            ]) },
         )
     }
 }
 
-impl std::ops::Sub<Simd32x4> for Simd32x4 {
+impl core::ops::Sub<Simd32x4> for Simd32x4 {
     type Output = Simd32x4;
 
+    #[inline(always)]
     fn sub(self, other: Self) -> Self {
         match_architecture!(
             Self,
+            { portable: self.portable - other.portable },
             { f128: _mm_sub_ps(self.f128, other.f128) },
             { f128: vsubq_f32(self.f128, other.f128) },
             { v128: f32x4_sub(self.v128, other.v128) },
@@ -332,43 +590,50 @@ impl std::ops::Sub<Simd32x4> for Simd32x4 {
     }
 }
 
-impl std::ops::Sub<Simd32x3> for Simd32x3 {
+impl core::ops::Sub<Simd32x3> for Simd32x3 {
     type Output = Simd32x3;
 
+    #[inline(always)]
     fn sub(self, other: Self) -> Self {
         match_architecture!(
             Self,
             { v32x4: unsafe { self.v32x4 - other.v32x4 } },
-            { f32x3: [
+            { v32x4: Simd32x4::from([
                 self.f32x3[0] - other.f32x3[0],
                 self.f32x3[1] - other.f32x3[1],
                 self.f32x3[2] - other.f32x3[2],
-            ] },
+                0.0,
+            ]) },
         )
     }
 }
 
-impl std::ops::Sub<Simd32x2> for Simd32x2 {
+impl core::ops::Sub<Simd32x2> for Simd32x2 {
     type Output = Simd32x2;
 
+    #[inline(always)]
     fn sub(self, other: Self) -> Self {
         match_architecture!(
             Self,
             { v32x4: unsafe { self.v32x4 - other.v32x4 } },
-            { f32x2: [
+            { v32x4: Simd32x4::from([
                 self.f32x2[0] - other.f32x2[0],
                 self.f32x2[1] - other.f32x2[1],
-            ] },
+                0.0,
+                0.0,
+            ]) },
         )
     }
 }
 
-impl std::ops::Mul<Simd32x4> for Simd32x4 {
+impl core::ops::Mul<Simd32x4> for Simd32x4 {
     type Output = Simd32x4;
 
+    #[inline(always)]
     fn mul(self, other: Self) -> Self {
         match_architecture!(
             Self,
+            { portable: self.portable * other.portable },
             { f128: _mm_mul_ps(self.f128, other.f128) },
             { f128: vmulq_f32(self.f128, other.f128) },
             { v128: f32x4_mul(self.v128, other.v128) },
@@ -382,43 +647,50 @@ impl std::ops::Mul<Simd32x4> for Simd32x4 {
     }
 }
 
-impl std::ops::Mul<Simd32x3> for Simd32x3 {
+impl core::ops::Mul<Simd32x3> for Simd32x3 {
     type Output = Simd32x3;
 
+    #[inline(always)]
     fn mul(self, other: Self) -> Self {
         match_architecture!(
             Self,
             { v32x4: unsafe { self.v32x4 * other.v32x4 } },
-            { f32x3: [
+            { v32x4: Simd32x4::from([
                 self.f32x3[0] * other.f32x3[0],
                 self.f32x3[1] * other.f32x3[1],
                 self.f32x3[2] * other.f32x3[2],
-            ] },
+                0.0,
+            ]) },
         )
     }
 }
 
-impl std::ops::Mul<Simd32x2> for Simd32x2 {
+impl core::ops::Mul<Simd32x2> for Simd32x2 {
     type Output = Simd32x2;
 
+    #[inline(always)]
     fn mul(self, other: Self) -> Self {
         match_architecture!(
             Self,
             { v32x4: unsafe { self.v32x4 * other.v32x4 } },
-            { f32x2: [
+            { v32x4: Simd32x4::from([
                 self.f32x2[0] * other.f32x2[0],
                 self.f32x2[1] * other.f32x2[1],
-            ] },
+                0.0,
+                0.0,
+            ]) },
         )
     }
 }
 
-impl std::ops::Div<Simd32x4> for Simd32x4 {
+impl core::ops::Div<Simd32x4> for Simd32x4 {
     type Output = Simd32x4;
 
+    #[inline(always)]
     fn div(self, other: Self) -> Self {
         match_architecture!(
             Self,
+            { portable: self.portable / other.portable },
             { f128: _mm_div_ps(self.f128, other.f128) },
             { f128: vdivq_f32(self.f128, other.f128) },
             { v128: f32x4_div(self.v128, other.v128) },
@@ -432,33 +704,852 @@ impl std::ops::Div<Simd32x4> for Simd32x4 {
     }
 }
 
-impl std::ops::Div<Simd32x3> for Simd32x3 {
+impl core::ops::Div<Simd32x3> for Simd32x3 {
     type Output = Simd32x3;
 
+    #[inline(always)]
     fn div(self, other: Self) -> Self {
         match_architecture!(
             Self,
             { v32x4: unsafe { self.v32x4 / other.v32x4 } },
-            { f32x3: [
+            { v32x4: Simd32x4::from([
                 self.f32x3[0] / other.f32x3[0],
                 self.f32x3[1] / other.f32x3[1],
                 self.f32x3[2] / other.f32x3[2],
-            ] },
+                0.0,
+            ]) },
         )
     }
 }
 
-impl std::ops::Div<Simd32x2> for Simd32x2 {
+impl core::ops::Div<Simd32x2> for Simd32x2 {
     type Output = Simd32x2;
 
+    #[inline(always)]
     fn div(self, other: Self) -> Self {
         match_architecture!(
             Self,
             { v32x4: unsafe { self.v32x4 / other.v32x4 } },
-            { f32x2: [
+            { v32x4: Simd32x4::from([
                 self.f32x2[0] / other.f32x2[0],
                 self.f32x2[1] / other.f32x2[1],
+                0.0,
+                0.0,
+            ]) },
+        )
+    }
+}
+
+impl Simd32x4 {
+    /// Permutes the lanes for [swizzle] with the `portable_simd` feature, as `simd_swizzle!` is unstable in the crate it is expanded in
+    #[cfg(feature = "portable_simd")]
+    #[inline(always)]
+    pub fn swizzle<const X: usize, const Y: usize, const Z: usize, const W: usize>(self) -> Self {
+        struct Indices<const X: usize, const Y: usize, const Z: usize, const W: usize>;
+        impl<const X: usize, const Y: usize, const Z: usize, const W: usize> std::simd::Swizzle<4> for Indices<X, Y, Z, W> {
+            const INDEX: [usize; 4] = [X, Y, Z, W];
+        }
+        Self {
+            portable: <Indices<X, Y, Z, W> as std::simd::Swizzle<4>>::swizzle(unsafe { self.portable }),
+        }
+    }
+
+    /// Square root of each lane
+    #[inline(always)]
+    pub fn sqrt(self) -> Self {
+        match_architecture!(
+            Self,
+            { portable: self.portable.sqrt() },
+            { f128: _mm_sqrt_ps(self.f128) },
+            // 32 bit ARM has no vector square root
+            { f32x4: [
+                self.f32x4[0].sqrt(),
+                self.f32x4[1].sqrt(),
+                self.f32x4[2].sqrt(),
+                self.f32x4[3].sqrt(),
+            ] },
+            { v128: f32x4_sqrt(self.v128) },
+            { f32x4: [
+                self.f32x4[0].sqrt(),
+                self.f32x4[1].sqrt(),
+                self.f32x4[2].sqrt(),
+                self.f32x4[3].sqrt(),
+            ] },
+        )
+    }
+
+    /// Computes `self * b + c` in each lane, fused into one instruction with a single rounding on x86 with FMA3 and on aarch64
+    /// (and with the `portable_simd` feature also on RISC-V and LoongArch)
+    #[inline(always)]
+    pub fn mul_add(self, b: Self, c: Self) -> Self {
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "fma"))]
+        unsafe {
+            Self {
+                f128: _mm_fmadd_ps(self.f128, b.f128, c.f128),
+            }
+        }
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        unsafe {
+            Self {
+                f128: vfmaq_f32(c.f128, self.f128, b.f128),
+            }
+        }
+        // Only where the FPU has fused multiply-add, as it is emulated in software otherwise
+        #[cfg(all(
+            feature = "portable_simd",
+            any(all(any(target_arch = "riscv32", target_arch = "riscv64"), target_feature = "f"), target_arch = "loongarch64"),
+        ))]
+        unsafe {
+            Self {
+                portable: self.portable.mul_add(b.portable, c.portable),
+            }
+        }
+        #[cfg(not(any(
+            all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "fma"),
+            all(target_arch = "aarch64", target_feature = "neon"),
+            all(
+                feature = "portable_simd",
+                any(all(any(target_arch = "riscv32", target_arch = "riscv64"), target_feature = "f"), target_arch = "loongarch64"),
+            ),
+        )))]
+        {
+            self * b + c
+        }
+    }
+}
+
+impl Simd32x3 {
+    /// See [Simd32x4::mul_add]
+    #[inline(always)]
+    pub fn mul_add(self, b: Self, c: Self) -> Self {
+        match_architecture!(
+            Self,
+            { v32x4: unsafe { self.v32x4.mul_add(b.v32x4, c.v32x4) } },
+            { v32x4: Simd32x4::from([
+                self.f32x3[0] * b.f32x3[0] + c.f32x3[0],
+                self.f32x3[1] * b.f32x3[1] + c.f32x3[1],
+                self.f32x3[2] * b.f32x3[2] + c.f32x3[2],
+                0.0,
+            ]) },
+        )
+    }
+}
+
+impl Simd32x2 {
+    /// See [Simd32x4::mul_add]
+    #[inline(always)]
+    pub fn mul_add(self, b: Self, c: Self) -> Self {
+        match_architecture!(
+            Self,
+            { v32x4: unsafe { self.v32x4.mul_add(b.v32x4, c.v32x4) } },
+            { v32x4: Simd32x4::from([
+                self.f32x2[0] * b.f32x2[0] + c.f32x2[0],
+                self.f32x2[1] * b.f32x2[1] + c.f32x2[1],
+                0.0,
+                0.0,
+            ]) },
+        )
+    }
+}
+
+impl core::ops::Index<usize> for Simd64x4 {
+    type Output = f64;
+
+    #[inline(always)]
+    fn index(&self, index: usize) -> &Self::Output {
+        unsafe { &self.f64x4[index] }
+    }
+}
+
+impl core::ops::Index<usize> for Simd64x3 {
+    type Output = f64;
+
+    #[inline(always)]
+    fn index(&self, index: usize) -> &Self::Output {
+        unsafe { &self.f64x3[index] }
+    }
+}
+
+impl core::ops::Index<usize> for Simd64x2 {
+    type Output = f64;
+
+    #[inline(always)]
+    fn index(&self, index: usize) -> &Self::Output {
+        unsafe { &self.f64x2[index] }
+    }
+}
+
+impl core::ops::IndexMut<usize> for Simd64x4 {
+    #[inline(always)]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        unsafe { &mut self.f64x4[index] }
+    }
+}
+
+impl core::ops::IndexMut<usize> for Simd64x3 {
+    #[inline(always)]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        unsafe { &mut self.f64x3[index] }
+    }
+}
+
+impl core::ops::IndexMut<usize> for Simd64x2 {
+    #[inline(always)]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        unsafe { &mut self.f64x2[index] }
+    }
+}
+
+impl core::convert::From<Simd64x4> for [f64; 4] {
+    #[inline(always)]
+    fn from(simd: Simd64x4) -> Self {
+        unsafe { simd.f64x4 }
+    }
+}
+
+impl core::convert::From<Simd64x3> for [f64; 3] {
+    #[inline(always)]
+    fn from(simd: Simd64x3) -> Self {
+        unsafe { simd.f64x3 }
+    }
+}
+
+impl core::convert::From<Simd64x2> for [f64; 2] {
+    #[inline(always)]
+    fn from(simd: Simd64x2) -> Self {
+        unsafe { simd.f64x2 }
+    }
+}
+
+impl core::convert::From<[f64; 4]> for Simd64x4 {
+    #[inline(always)]
+    fn from(f64x4: [f64; 4]) -> Self {
+        Self { f64x4 }
+    }
+}
+
+impl core::convert::From<[f64; 3]> for Simd64x3 {
+    #[inline(always)]
+    fn from(f64x3: [f64; 3]) -> Self {
+        Self {
+            v64x4: Simd64x4::from([f64x3[0], f64x3[1], f64x3[2], 0.0]),
+        }
+    }
+}
+
+impl core::convert::From<[f64; 2]> for Simd64x2 {
+    #[inline(always)]
+    fn from(f64x2: [f64; 2]) -> Self {
+        Self {
+            v64x4: Simd64x4::from([f64x2[0], f64x2[1], 0.0, 0.0]),
+        }
+    }
+}
+
+impl core::convert::From<f64> for Simd64x4 {
+    #[inline(always)]
+    fn from(value: f64) -> Self {
+        Self {
+            f64x4: [value, value, value, value],
+        }
+    }
+}
+
+impl core::convert::From<f64> for Simd64x3 {
+    #[inline(always)]
+    fn from(value: f64) -> Self {
+        Self {
+            v64x4: Simd64x4::from([value, value, value, 0.0]),
+        }
+    }
+}
+
+impl core::convert::From<f64> for Simd64x2 {
+    #[inline(always)]
+    fn from(value: f64) -> Self {
+        Self {
+            v64x4: Simd64x4::from([value, value, 0.0, 0.0]),
+        }
+    }
+}
+
+impl core::fmt::Debug for Simd64x4 {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_list()
+            .entries([self[0], self[1], self[2], self[3]].iter())
+            .finish()
+    }
+}
+
+impl core::fmt::Debug for Simd64x3 {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_list()
+            .entries([self[0], self[1], self[2]].iter())
+            .finish()
+    }
+}
+
+impl core::fmt::Debug for Simd64x2 {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter
+            .debug_list()
+            .entries([self[0], self[1]].iter())
+            .finish()
+    }
+}
+
+impl core::ops::Add<Simd64x4> for Simd64x4 {
+    type Output = Simd64x4;
+
+    #[inline(always)]
+    fn add(self, other: Self) -> Self {
+        match_architecture_f64!(
+            Self,
+            { f256: _mm256_add_pd(self.f256, other.f256) },
+            { f128: [_mm_add_pd(self.f128[0], other.f128[0]), _mm_add_pd(self.f128[1], other.f128[1])] },
+            { f128: [vaddq_f64(self.f128[0], other.f128[0]), vaddq_f64(self.f128[1], other.f128[1])] },
+            { v128: [f64x2_add(self.v128[0], other.v128[0]), f64x2_add(self.v128[1], other.v128[1])] },
+            { f64x4: [
+                self.f64x4[0] + other.f64x4[0],
+                self.f64x4[1] + other.f64x4[1],
+                self.f64x4[2] + other.f64x4[2],
+                self.f64x4[3] + other.f64x4[3],
+            ] },
+        )
+    }
+}
+
+impl core::ops::Add<Simd64x3> for Simd64x3 {
+    type Output = Simd64x3;
+
+    #[inline(always)]
+    fn add(self, other: Self) -> Self {
+        match_architecture!(
+            Self,
+            { v64x4: unsafe { self.v64x4 + other.v64x4 } },
+            { v64x4: Simd64x4::from([
+                self.f64x3[0] + other.f64x3[0],
+                self.f64x3[1] + other.f64x3[1],
+                self.f64x3[2] + other.f64x3[2],
+                0.0,
+            ]) },
+        )
+    }
+}
+
+impl core::ops::Add<Simd64x2> for Simd64x2 {
+    type Output = Simd64x2;
+
+    #[inline(always)]
+    fn add(self, other: Self) -> Self {
+        match_architecture!(
+            Self,
+            { v64x4: unsafe { self.v64x4 + other.v64x4 } },
+            { v64x4: Simd64x4::from([
+                self.f64x2[0] + other.f64x2[0],
+                self.f64x2[1] + other.f64x2[1],
+                0.0,
+                0.0,
+            ]) },
+        )
+    }
+}
+
+impl core::ops::Sub<Simd64x4> for Simd64x4 {
+    type Output = Simd64x4;
+
+    #[inline(always)]
+    fn sub(self, other: Self) -> Self {
+        match_architecture_f64!(
+            Self,
+            { f256: _mm256_sub_pd(self.f256, other.f256) },
+            { f128: [_mm_sub_pd(self.f128[0], other.f128[0]), _mm_sub_pd(self.f128[1], other.f128[1])] },
+            { f128: [vsubq_f64(self.f128[0], other.f128[0]), vsubq_f64(self.f128[1], other.f128[1])] },
+            { v128: [f64x2_sub(self.v128[0], other.v128[0]), f64x2_sub(self.v128[1], other.v128[1])] },
+            { f64x4: [
+                self.f64x4[0] - other.f64x4[0],
+                self.f64x4[1] - other.f64x4[1],
+                self.f64x4[2] - other.f64x4[2],
+                self.f64x4[3] - other.f64x4[3],
+            ] },
+        )
+    }
+}
+
+impl core::ops::Sub<Simd64x3> for Simd64x3 {
+    type Output = Simd64x3;
+
+    #[inline(always)]
+    fn sub(self, other: Self) -> Self {
+        match_architecture!(
+            Self,
+            { v64x4: unsafe { self.v64x4 - other.v64x4 } },
+            { v64x4: Simd64x4::from([
+                self.f64x3[0] - other.f64x3[0],
+                self.f64x3[1] - other.f64x3[1],
+                self.f64x3[2] - other.f64x3[2],
+                0.0,
+            ]) },
+        )
+    }
+}
+
+impl core::ops::Sub<Simd64x2> for Simd64x2 {
+    type Output = Simd64x2;
+
+    #[inline(always)]
+    fn sub(self, other: Self) -> Self {
+        match_architecture!(
+            Self,
+            { v64x4: unsafe { self.v64x4 - other.v64x4 } },
+            { v64x4: Simd64x4::from([
+                self.f64x2[0] - other.f64x2[0],
+                self.f64x2[1] - other.f64x2[1],
+                0.0,
+                0.0,
+            ]) },
+        )
+    }
+}
+
+impl core::ops::Mul<Simd64x4> for Simd64x4 {
+    type Output = Simd64x4;
+
+    #[inline(always)]
+    fn mul(self, other: Self) -> Self {
+        match_architecture_f64!(
+            Self,
+            { f256: _mm256_mul_pd(self.f256, other.f256) },
+            { f128: [_mm_mul_pd(self.f128[0], other.f128[0]), _mm_mul_pd(self.f128[1], other.f128[1])] },
+            { f128: [vmulq_f64(self.f128[0], other.f128[0]), vmulq_f64(self.f128[1], other.f128[1])] },
+            { v128: [f64x2_mul(self.v128[0], other.v128[0]), f64x2_mul(self.v128[1], other.v128[1])] },
+            { f64x4: [
+                self.f64x4[0] * other.f64x4[0],
+                self.f64x4[1] * other.f64x4[1],
+                self.f64x4[2] * other.f64x4[2],
+                self.f64x4[3] * other.f64x4[3],
             ] },
         )
     }
 }
+
+impl core::ops::Mul<Simd64x3> for Simd64x3 {
+    type Output = Simd64x3;
+
+    #[inline(always)]
+    fn mul(self, other: Self) -> Self {
+        match_architecture!(
+            Self,
+            { v64x4: unsafe { self.v64x4 * other.v64x4 } },
+            { v64x4: Simd64x4::from([
+                self.f64x3[0] * other.f64x3[0],
+                self.f64x3[1] * other.f64x3[1],
+                self.f64x3[2] * other.f64x3[2],
+                0.0,
+            ]) },
+        )
+    }
+}
+
+impl core::ops::Mul<Simd64x2> for Simd64x2 {
+    type Output = Simd64x2;
+
+    #[inline(always)]
+    fn mul(self, other: Self) -> Self {
+        match_architecture!(
+            Self,
+            { v64x4: unsafe { self.v64x4 * other.v64x4 } },
+            { v64x4: Simd64x4::from([
+                self.f64x2[0] * other.f64x2[0],
+                self.f64x2[1] * other.f64x2[1],
+                0.0,
+                0.0,
+            ]) },
+        )
+    }
+}
+
+impl core::ops::Div<Simd64x4> for Simd64x4 {
+    type Output = Simd64x4;
+
+    #[inline(always)]
+    fn div(self, other: Self) -> Self {
+        match_architecture_f64!(
+            Self,
+            { f256: _mm256_div_pd(self.f256, other.f256) },
+            { f128: [_mm_div_pd(self.f128[0], other.f128[0]), _mm_div_pd(self.f128[1], other.f128[1])] },
+            { f128: [vdivq_f64(self.f128[0], other.f128[0]), vdivq_f64(self.f128[1], other.f128[1])] },
+            { v128: [f64x2_div(self.v128[0], other.v128[0]), f64x2_div(self.v128[1], other.v128[1])] },
+            { f64x4: [
+                self.f64x4[0] / other.f64x4[0],
+                self.f64x4[1] / other.f64x4[1],
+                self.f64x4[2] / other.f64x4[2],
+                self.f64x4[3] / other.f64x4[3],
+            ] },
+        )
+    }
+}
+
+impl core::ops::Div<Simd64x3> for Simd64x3 {
+    type Output = Simd64x3;
+
+    #[inline(always)]
+    fn div(self, other: Self) -> Self {
+        match_architecture!(
+            Self,
+            { v64x4: unsafe { self.v64x4 / other.v64x4 } },
+            { v64x4: Simd64x4::from([
+                self.f64x3[0] / other.f64x3[0],
+                self.f64x3[1] / other.f64x3[1],
+                self.f64x3[2] / other.f64x3[2],
+                0.0,
+            ]) },
+        )
+    }
+}
+
+impl core::ops::Div<Simd64x2> for Simd64x2 {
+    type Output = Simd64x2;
+
+    #[inline(always)]
+    fn div(self, other: Self) -> Self {
+        match_architecture!(
+            Self,
+            { v64x4: unsafe { self.v64x4 / other.v64x4 } },
+            { v64x4: Simd64x4::from([
+                self.f64x2[0] / other.f64x2[0],
+                self.f64x2[1] / other.f64x2[1],
+                0.0,
+                0.0,
+            ]) },
+        )
+    }
+}
+
+impl Simd64x4 {
+    /// Square root of each lane
+    #[inline(always)]
+    pub fn sqrt(self) -> Self {
+        match_architecture_f64!(
+            Self,
+            { f256: _mm256_sqrt_pd(self.f256) },
+            { f128: [_mm_sqrt_pd(self.f128[0]), _mm_sqrt_pd(self.f128[1])] },
+            { f128: [vsqrtq_f64(self.f128[0]), vsqrtq_f64(self.f128[1])] },
+            { v128: [f64x2_sqrt(self.v128[0]), f64x2_sqrt(self.v128[1])] },
+            { f64x4: [
+                self.f64x4[0].sqrt(),
+                self.f64x4[1].sqrt(),
+                self.f64x4[2].sqrt(),
+                self.f64x4[3].sqrt(),
+            ] },
+        )
+    }
+
+    /// Computes `self * b + c` in each lane, fused into one instruction with a single rounding on x86 with FMA3 and on aarch64
+    #[inline(always)]
+    pub fn mul_add(self, b: Self, c: Self) -> Self {
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "fma"))]
+        unsafe {
+            Self {
+                f256: _mm256_fmadd_pd(self.f256, b.f256, c.f256),
+            }
+        }
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        unsafe {
+            Self {
+                f128: [vfmaq_f64(c.f128[0], self.f128[0], b.f128[0]), vfmaq_f64(c.f128[1], self.f128[1], b.f128[1])],
+            }
+        }
+        #[cfg(not(any(
+            all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "fma"),
+            all(target_arch = "aarch64", target_feature = "neon"),
+        )))]
+        {
+            self * b + c
+        }
+    }
+}
+
+impl Simd64x3 {
+    /// See [Simd64x4::mul_add]
+    #[inline(always)]
+    pub fn mul_add(self, b: Self, c: Self) -> Self {
+        match_architecture!(
+            Self,
+            { v64x4: unsafe { self.v64x4.mul_add(b.v64x4, c.v64x4) } },
+            { v64x4: Simd64x4::from([
+                self.f64x3[0] * b.f64x3[0] + c.f64x3[0],
+                self.f64x3[1] * b.f64x3[1] + c.f64x3[1],
+                self.f64x3[2] * b.f64x3[2] + c.f64x3[2],
+                0.0,
+            ]) },
+        )
+    }
+}
+
+impl Simd64x2 {
+    /// See [Simd64x4::mul_add]
+    #[inline(always)]
+    pub fn mul_add(self, b: Self, c: Self) -> Self {
+        match_architecture!(
+            Self,
+            { v64x4: unsafe { self.v64x4.mul_add(b.v64x4, c.v64x4) } },
+            { v64x4: Simd64x4::from([
+                self.f64x2[0] * b.f64x2[0] + c.f64x2[0],
+                self.f64x2[1] * b.f64x2[1] + c.f64x2[1],
+                0.0,
+                0.0,
+            ]) },
+        )
+    }
+}
+
+impl core::ops::Index<usize> for Simd32x8 {
+    type Output = f32;
+
+    #[inline(always)]
+    fn index(&self, index: usize) -> &Self::Output {
+        unsafe { &self.f32x8[index] }
+    }
+}
+
+impl core::ops::IndexMut<usize> for Simd32x8 {
+    #[inline(always)]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        unsafe { &mut self.f32x8[index] }
+    }
+}
+
+impl core::convert::From<Simd32x8> for [f32; 8] {
+    #[inline(always)]
+    fn from(simd: Simd32x8) -> Self {
+        unsafe { simd.f32x8 }
+    }
+}
+
+impl core::convert::From<[f32; 8]> for Simd32x8 {
+    #[inline(always)]
+    fn from(f32x8: [f32; 8]) -> Self {
+        Self { f32x8 }
+    }
+}
+
+impl core::convert::From<f32> for Simd32x8 {
+    #[inline(always)]
+    fn from(value: f32) -> Self {
+        Self {
+            f32x8: [value; 8],
+        }
+    }
+}
+
+impl core::fmt::Debug for Simd32x8 {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.debug_list().entries(unsafe { self.f32x8 }.iter()).finish()
+    }
+}
+
+impl core::ops::Add<Simd32x8> for Simd32x8 {
+    type Output = Simd32x8;
+
+    #[inline(always)]
+    fn add(self, other: Self) -> Self {
+        match_architecture_avx!(
+            Self,
+            { f256: _mm256_add_ps(self.f256, other.f256) },
+            { v32x4: [self.v32x4[0] + other.v32x4[0], self.v32x4[1] + other.v32x4[1]] },
+        )
+    }
+}
+
+impl core::ops::Sub<Simd32x8> for Simd32x8 {
+    type Output = Simd32x8;
+
+    #[inline(always)]
+    fn sub(self, other: Self) -> Self {
+        match_architecture_avx!(
+            Self,
+            { f256: _mm256_sub_ps(self.f256, other.f256) },
+            { v32x4: [self.v32x4[0] - other.v32x4[0], self.v32x4[1] - other.v32x4[1]] },
+        )
+    }
+}
+
+impl core::ops::Mul<Simd32x8> for Simd32x8 {
+    type Output = Simd32x8;
+
+    #[inline(always)]
+    fn mul(self, other: Self) -> Self {
+        match_architecture_avx!(
+            Self,
+            { f256: _mm256_mul_ps(self.f256, other.f256) },
+            { v32x4: [self.v32x4[0] * other.v32x4[0], self.v32x4[1] * other.v32x4[1]] },
+        )
+    }
+}
+
+impl core::ops::Div<Simd32x8> for Simd32x8 {
+    type Output = Simd32x8;
+
+    #[inline(always)]
+    fn div(self, other: Self) -> Self {
+        match_architecture_avx!(
+            Self,
+            { f256: _mm256_div_ps(self.f256, other.f256) },
+            { v32x4: [self.v32x4[0] / other.v32x4[0], self.v32x4[1] / other.v32x4[1]] },
+        )
+    }
+}
+
+impl Simd32x8 {
+    /// Square root of each lane
+    #[inline(always)]
+    pub fn sqrt(self) -> Self {
+        match_architecture_avx!(
+            Self,
+            { f256: _mm256_sqrt_ps(self.f256) },
+            { v32x4: [self.v32x4[0].sqrt(), self.v32x4[1].sqrt()] },
+        )
+    }
+
+    /// Computes `self * b + c` in each lane, fused into one instruction with a single rounding if FMA is enabled
+    #[inline(always)]
+    pub fn mul_add(self, b: Self, c: Self) -> Self {
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "fma"))]
+        unsafe {
+            Self {
+                f256: _mm256_fmadd_ps(self.f256, b.f256, c.f256),
+            }
+        }
+        #[cfg(not(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "fma")))]
+        {
+            self * b + c
+        }
+    }
+}
+
+impl core::ops::Index<usize> for Simd64x8 {
+    type Output = f64;
+
+    #[inline(always)]
+    fn index(&self, index: usize) -> &Self::Output {
+        unsafe { &self.f64x8[index] }
+    }
+}
+
+impl core::ops::IndexMut<usize> for Simd64x8 {
+    #[inline(always)]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        unsafe { &mut self.f64x8[index] }
+    }
+}
+
+impl core::convert::From<Simd64x8> for [f64; 8] {
+    #[inline(always)]
+    fn from(simd: Simd64x8) -> Self {
+        unsafe { simd.f64x8 }
+    }
+}
+
+impl core::convert::From<[f64; 8]> for Simd64x8 {
+    #[inline(always)]
+    fn from(f64x8: [f64; 8]) -> Self {
+        Self { f64x8 }
+    }
+}
+
+impl core::convert::From<f64> for Simd64x8 {
+    #[inline(always)]
+    fn from(value: f64) -> Self {
+        Self {
+            f64x8: [value; 8],
+        }
+    }
+}
+
+impl core::fmt::Debug for Simd64x8 {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.debug_list().entries(unsafe { self.f64x8 }.iter()).finish()
+    }
+}
+
+impl core::ops::Add<Simd64x8> for Simd64x8 {
+    type Output = Simd64x8;
+
+    #[inline(always)]
+    fn add(self, other: Self) -> Self {
+        match_architecture_avx512!(
+            Self,
+            { f512: _mm512_add_pd(self.f512, other.f512) },
+            { v64x4: [self.v64x4[0] + other.v64x4[0], self.v64x4[1] + other.v64x4[1]] },
+        )
+    }
+}
+
+impl core::ops::Sub<Simd64x8> for Simd64x8 {
+    type Output = Simd64x8;
+
+    #[inline(always)]
+    fn sub(self, other: Self) -> Self {
+        match_architecture_avx512!(
+            Self,
+            { f512: _mm512_sub_pd(self.f512, other.f512) },
+            { v64x4: [self.v64x4[0] - other.v64x4[0], self.v64x4[1] - other.v64x4[1]] },
+        )
+    }
+}
+
+impl core::ops::Mul<Simd64x8> for Simd64x8 {
+    type Output = Simd64x8;
+
+    #[inline(always)]
+    fn mul(self, other: Self) -> Self {
+        match_architecture_avx512!(
+            Self,
+            { f512: _mm512_mul_pd(self.f512, other.f512) },
+            { v64x4: [self.v64x4[0] * other.v64x4[0], self.v64x4[1] * other.v64x4[1]] },
+        )
+    }
+}
+
+impl core::ops::Div<Simd64x8> for Simd64x8 {
+    type Output = Simd64x8;
+
+    #[inline(always)]
+    fn div(self, other: Self) -> Self {
+        match_architecture_avx512!(
+            Self,
+            { f512: _mm512_div_pd(self.f512, other.f512) },
+            { v64x4: [self.v64x4[0] / other.v64x4[0], self.v64x4[1] / other.v64x4[1]] },
+        )
+    }
+}
+
+impl Simd64x8 {
+    /// Square root of each lane
+    #[inline(always)]
+    pub fn sqrt(self) -> Self {
+        match_architecture_avx512!(
+            Self,
+            { f512: _mm512_sqrt_pd(self.f512) },
+            { v64x4: [self.v64x4[0].sqrt(), self.v64x4[1].sqrt()] },
+        )
+    }
+
+    /// Computes `self * b + c` in each lane, fused into one instruction with a single rounding if AVX-512 is enabled
+    #[inline(always)]
+    pub fn mul_add(self, b: Self, c: Self) -> Self {
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "avx512f"))]
+        unsafe {
+            Self {
+                f512: _mm512_fmadd_pd(self.f512, b.f512, c.f512),
+            }
+        }
+        #[cfg(not(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "avx512f")))]
+        {
+            self * b + c
+        }
+    }
+}
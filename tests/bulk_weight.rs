@@ -0,0 +1,35 @@
+use geometric_algebra::{ppga3d, Bulk, BulkNorm, Exp, IdealNorm, Magnitude, Weight, WeightNorm};
+
+#[test]
+fn ppga3d_ideal_point() {
+    // The direction (3, 0, 4) at infinity, whose e123 part is zero
+    let point = ppga3d::Point::new(0.0, 3.0, 0.0, 4.0);
+    assert_eq!(point.magnitude(), 0.0);
+    assert_eq!(point.weight_norm(), 0.0);
+    assert_eq!(point.ideal_norm(), 5.0);
+    assert_eq!(point.bulk_norm(), 5.0);
+}
+
+#[test]
+fn ppga3d_normalized_point() {
+    let point = ppga3d::Point::new(2.0, 2.0, -4.0, 8.0);
+    assert_eq!(point.weight_norm(), 2.0);
+    assert!((point.bulk_norm() / point.weight_norm() - 21.0f32.sqrt()).abs() < 1.0e-6);
+}
+
+#[test]
+fn ppga3d_bulk_plus_weight() {
+    let point = ppga3d::Point::new(2.0, 3.0, -1.0, 4.0);
+    assert_eq!(<[f32; 4]>::from(point.bulk()), [0.0, 3.0, -1.0, 4.0]);
+    assert_eq!(<[f32; 4]>::from(point.weight()), [2.0, 0.0, 0.0, 0.0]);
+    assert_eq!(<[f32; 4]>::from(point.bulk() + point.weight()), <[f32; 4]>::from(point));
+    let plane = ppga3d::Plane::new(5.0, 0.6, 0.0, -0.8);
+    assert_eq!(<[f32; 4]>::from(plane.bulk()), [5.0, 0.0, 0.0, 0.0]);
+    assert_eq!(<[f32; 4]>::from(plane.bulk() + plane.weight()), <[f32; 4]>::from(plane));
+    let line = ppga3d::Line::new(0.3, -0.2, 0.5, 0.7, 0.1, -0.4);
+    assert_eq!(<[f32; 6]>::from(line.bulk() + line.weight()), <[f32; 6]>::from(line));
+    let motor = line.exp();
+    let [_, _, _, _, e0123, e01, e02, e03]: [f32; 8] = motor.into();
+    assert_eq!(<[f32; 8]>::from(motor.bulk()), [0.0, 0.0, 0.0, 0.0, e0123, e01, e02, e03]);
+    assert_eq!(<[f32; 8]>::from(motor.bulk() + motor.weight()), <[f32; 8]>::from(motor));
+}
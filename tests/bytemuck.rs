@@ -0,0 +1,45 @@
+#![cfg(feature = "bytemuck")]
+use geometric_algebra::{
+    bytemuck, ppga3d, ppga3d_f64,
+    simd::{Simd32x3, Simd64x3},
+    RegressiveProduct,
+};
+
+#[test]
+fn line_round_trip() {
+    let line = ppga3d::Line::new(1.0, 2.0, 3.0, 4.0, 5.0, 6.0);
+    let bytes = bytemuck::bytes_of(&line);
+    assert_eq!(bytes.len(), 32);
+    assert_eq!(bytemuck::cast_slice::<u8, f32>(bytes), [1.0, 2.0, 3.0, 0.0, 4.0, 5.0, 6.0, 0.0]);
+    let copy: ppga3d::Line = bytemuck::pod_read_unaligned(bytes);
+    assert_eq!(<[f32; 6]>::from(copy), [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+    // Constructed from groups and computed by the generated code
+    let line = ppga3d::Line::from_groups(Simd32x3::from([1.0, 2.0, 3.0]), Simd32x3::from(4.0));
+    assert_eq!(
+        bytemuck::cast_slice::<u8, f32>(bytemuck::bytes_of(&line)),
+        [1.0, 2.0, 3.0, 0.0, 4.0, 4.0, 4.0, 0.0]
+    );
+    let line = ppga3d::Point::new(1.0, 2.0, 3.0, 4.0).regressive_product(ppga3d::Point::new(1.0, -1.0, 0.0, 2.0)) + line;
+    let copy: ppga3d::Line = bytemuck::pod_read_unaligned(bytemuck::bytes_of(&line));
+    assert_eq!(<[f32; 6]>::from(copy), <[f32; 6]>::from(line));
+
+    // Written through the groups, which can only be constructed with the unused lane initialized
+    let mut line = ppga3d::Line::new(1.0, 2.0, 3.0, 4.0, 5.0, 6.0);
+    *line.group1_mut() = Simd32x3::from([7.0, 8.0, 9.0]);
+    line.group0_mut()[1] = -2.0;
+    assert_eq!(
+        bytemuck::cast_slice::<u8, f32>(bytemuck::bytes_of(&line)),
+        [1.0, -2.0, 3.0, 0.0, 7.0, 8.0, 9.0, 0.0]
+    );
+}
+
+#[test]
+fn line_f64_round_trip() {
+    let line = ppga3d_f64::Line::from_groups(Simd64x3::from([1.0, 2.0, 3.0]), Simd64x3::from(4.0));
+    let bytes = bytemuck::bytes_of(&line);
+    assert_eq!(bytes.len(), 64);
+    assert_eq!(bytemuck::cast_slice::<u8, f64>(bytes), [1.0, 2.0, 3.0, 0.0, 4.0, 4.0, 4.0, 0.0]);
+    let copy: ppga3d_f64::Line = bytemuck::pod_read_unaligned(bytes);
+    assert_eq!(<[f64; 6]>::from(copy), [1.0, 2.0, 3.0, 4.0, 4.0, 4.0]);
+}
@@ -0,0 +1,55 @@
+//! Helpers and fixtures shared by the integration tests
+#![allow(dead_code)]
+
+use geometric_algebra::{cga3d, ppga3d, sta, vga3d, Exp};
+
+/// Asserts that the elements are equal up to a relative tolerance, as closed forms and their series round differently
+pub fn assert_close<const N: usize>(a: [f32; N], b: [f32; N]) {
+    assert!(
+        a.iter().zip(b.iter()).all(|(a, b)| (a - b).abs() <= 1.0e-4 * (1.0 + b.abs())),
+        "{:?} != {:?}",
+        a,
+        b
+    );
+}
+
+/// A rotation, a translation, a screw motion and a large screw motion
+pub fn ppga3d_lines() -> [ppga3d::Line; 4] {
+    [
+        ppga3d::Line::new(0.0, 0.0, 0.0, 0.7, 0.1, -0.4),
+        ppga3d::Line::new(0.3, -0.2, 0.5, 0.0, 0.0, 0.0),
+        ppga3d::Line::new(0.3, -0.2, 0.5, 0.7, 0.1, -0.4),
+        ppga3d::Line::new(1.5, 0.0, -2.0, 0.0, 2.5, 0.5),
+    ]
+}
+
+/// A rotation, a boost and three combinations of both
+pub fn sta_bivectors() -> [sta::Bivector; 5] {
+    [
+        sta::Bivector::new(0.3, -0.2, 0.5, 0.0, 0.0, 0.0),
+        sta::Bivector::new(0.0, 0.0, 0.0, 0.7, 0.1, -0.4),
+        sta::Bivector::new(0.3, -0.2, 0.5, 0.7, 0.1, -0.4),
+        sta::Bivector::new(0.5, 0.0, 0.0, 0.0, 0.8, 0.0),
+        sta::Bivector::new(0.0, 0.0, 1.0, 1.0, 0.0, 0.0),
+    ]
+}
+
+/// A rotation, a dilation and two general conformal transformations
+pub fn cga3d_point_pairs() -> [cga3d::PointPair; 4] {
+    [
+        cga3d::PointPair::new(0.0, 0.0, 0.5, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0),
+        cga3d::PointPair::new(0.0, 0.0, 0.0, 0.4, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0),
+        cga3d::PointPair::new(0.3, -0.2, 0.5, 0.0, 0.4, 0.1, -0.3, 0.0, 0.0, 0.0),
+        cga3d::PointPair::new(0.3, -0.2, 0.5, 0.2, 0.4, 0.1, -0.3, -0.1, 0.2, 0.3),
+    ]
+}
+
+/// A rotation about and translation along the z axis, where `e3 ∧ (e0 + e4)` is the direction of the translation
+pub fn cga3d_screw() -> cga3d::Versor {
+    cga3d::PointPair::new(0.0, 0.0, 0.7, 0.0, 0.0, 0.0, -0.4, 0.0, 0.0, 0.4).exp()
+}
+
+/// A small and a large rotation
+pub fn vga3d_bivectors() -> [vga3d::Bivector; 2] {
+    [vga3d::Bivector::new(0.1, 0.2, -0.3), vga3d::Bivector::new(1.2, -0.8, 2.0)]
+}
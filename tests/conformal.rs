@@ -0,0 +1,60 @@
+mod common;
+
+use common::assert_close;
+use geometric_algebra::{cga3d, GeometricProduct, RegressiveProduct, ScalarProduct};
+
+const POINTS: [[f32; 3]; 3] = [[0.0, 0.0, 0.0], [1.0, 2.0, 3.0], [-0.5, 4.0, 2.5]];
+
+#[test]
+fn up_down() {
+    for x in POINTS {
+        let point = cga3d::Point::up(x);
+        assert_close::<1>([point.normalization_factor()], [1.0]);
+        assert_close::<1>([point.scalar_product(point)], [0.0]);
+        assert_close::<3>(point.down(), x);
+        assert_close::<3>(point.geometric_product(-3.0).down(), x);
+    }
+    assert_close::<5>(cga3d::Point::up([0.0; 3]).into(), cga3d::Point::origin().into());
+}
+
+#[test]
+fn squared_distance() {
+    for a in POINTS {
+        for b in POINTS {
+            let squared_distance = (0..3).map(|index| (a[index] - b[index]) * (a[index] - b[index])).sum::<f32>();
+            assert_close::<1>([-2.0 * cga3d::Point::up(a).scalar_product(cga3d::Point::up(b))], [squared_distance]);
+        }
+    }
+}
+
+#[test]
+fn sphere() {
+    for center in POINTS {
+        let sphere = cga3d::Sphere::from_center_radius(center, 1.5);
+        assert_close::<3>(sphere.center(), center);
+        assert_close::<1>([sphere.squared_radius()], [2.25]);
+        let dual_point = sphere.dual_point();
+        for direction in [[1.0, 0.0, 0.0], [0.0, -1.0, 0.0], [0.6, 0.0, 0.8]] {
+            let on_sphere = cga3d::Point::up([0, 1, 2].map(|index| center[index] + 1.5 * direction[index]));
+            assert_close::<1>([dual_point.scalar_product(on_sphere)], [0.0]);
+            let inside = cga3d::Point::up([0, 1, 2].map(|index| center[index] + 0.5 * direction[index]));
+            assert!(dual_point.scalar_product(inside).abs() > 0.1);
+        }
+    }
+}
+
+#[test]
+fn plane() {
+    let (normal, distance) = ([0.6, 0.0, -0.8], 2.0);
+    let plane = cga3d::Plane::from_normal_distance(normal, distance);
+    for tangent in [[0.0, 1.0, 0.0], [0.8, 0.0, 0.6], [0.0, 0.0, 0.0]] {
+        let on_plane = cga3d::Point::up([0, 1, 2].map(|index| distance * normal[index] + 3.0 * tangent[index]));
+        assert_close::<1>([plane.regressive_product(on_plane)], [0.0]);
+    }
+    assert!(plane.regressive_product(cga3d::Point::origin()).abs() > 0.1);
+    let sphere: cga3d::Sphere = plane.into();
+    let dual_point = sphere.dual_point();
+    let scale = (0..3).map(|index| dual_point[index] * dual_point[index]).sum::<f32>().sqrt();
+    assert_close::<3>([0, 1, 2].map(|index| dual_point[index] / scale), normal);
+    assert_close::<1>([dual_point[3] / scale], [distance]);
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use common::{assert_close, cga3d_point_pairs, ppga3d_lines, sta_bivectors, vga3d_bivectors};
+use geometric_algebra::{cga3d, ppga3d, sta, vga3d, Exp, GeometricProduct, Ln, One, Powf};
+
+/// Checks `exp` against its power series, `ln` as its inverse and that `powf(0.5)` squares back
+macro_rules! check_exp_ln_powf {
+    ($bivector:expr, $Bivector:ty, $Rotor:ty, $n:literal, $m:literal) => {{
+        let bivector: $Bivector = $bivector;
+        let rotor: $Rotor = bivector.exp();
+        let mut term = <$Rotor>::one();
+        let mut series = term;
+        for n in 1..32 {
+            term = term.geometric_product(bivector).geometric_product(1.0 / n as f32);
+            series += term;
+        }
+        assert_close::<$m>(rotor.into(), series.into());
+        assert_close::<$n>(rotor.ln().into(), bivector.into());
+        let half = rotor.powf(0.5);
+        assert_close::<$m>(half.geometric_product(half).into(), rotor.into());
+    }};
+}
+
+#[test]
+fn ppga3d_line() {
+    for line in ppga3d_lines() {
+        check_exp_ln_powf!(line, ppga3d::Line, ppga3d::Motor, 6, 8);
+    }
+}
+
+#[test]
+fn sta_bivector() {
+    for bivector in sta_bivectors() {
+        check_exp_ln_powf!(bivector, sta::Bivector, sta::Spinor, 6, 8);
+    }
+}
+
+#[test]
+fn cga3d_point_pair() {
+    for point_pair in cga3d_point_pairs() {
+        check_exp_ln_powf!(point_pair, cga3d::PointPair, cga3d::Versor, 10, 16);
+    }
+}
+
+#[test]
+fn vga3d_bivector() {
+    for bivector in vga3d_bivectors() {
+        check_exp_ln_powf!(bivector, vga3d::Bivector, vga3d::Rotor, 3, 4);
+    }
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use common::{assert_close, cga3d_point_pairs, cga3d_screw, ppga3d_lines, sta_bivectors, vga3d_bivectors};
+use geometric_algebra::{cga3d, ppga3d, sta, vga3d, Exp, GeometricProduct, Normalize, One, Reversal};
+
+/// Adds a drift to each element of the rotor, like the accumulated rounding errors of many products
+fn drift<const N: usize>(rotor: [f32; N]) -> [f32; N] {
+    let mut result = rotor;
+    for (index, element) in result.iter_mut().enumerate() {
+        *element = *element * 1.1 + 0.02 * (index as f32 - 2.5);
+    }
+    result
+}
+
+/// Checks that the normalization of a drifted rotor satisfies `R * ~R = 1`, including its quadvector part
+macro_rules! check_normalize {
+    ($rotor:expr, $Rotor:ty, $Normalized:ty, $n:literal, $m:literal) => {{
+        let rotor: $Rotor = <$Rotor>::from(drift::<$n>($rotor.into()));
+        let normalized = rotor.normalize();
+        assert_close::<$m>(normalized.geometric_product(normalized.reversal()).into(), <$Normalized>::one().into());
+    }};
+}
+
+#[test]
+fn ppga3d_motor() {
+    for line in ppga3d_lines() {
+        let motor = line.exp();
+        let drifted = ppga3d::Motor::from(drift::<8>(motor.into()));
+        let squared_magnitude: [f32; 8] = drifted.geometric_product(drifted.reversal()).into();
+        assert!(squared_magnitude[4].abs() > 1.0e-3, "e0123 of {:?} has not drifted", squared_magnitude);
+        check_normalize!(motor, ppga3d::Motor, ppga3d::Motor, 8, 8);
+    }
+}
+
+#[test]
+fn ppga3d_normalized_motor_is_unchanged() {
+    for line in ppga3d_lines() {
+        let motor = line.exp();
+        assert_close::<8>(motor.normalize().into(), motor.into());
+    }
+}
+
+#[test]
+fn sta_spinor() {
+    for bivector in sta_bivectors() {
+        check_normalize!(bivector.exp(), sta::Spinor, sta::Spinor, 8, 8);
+    }
+}
+
+#[test]
+fn vga3d_rotor() {
+    for bivector in vga3d_bivectors() {
+        check_normalize!(bivector.exp(), vga3d::Rotor, vga3d::Rotor, 4, 4);
+    }
+}
+
+#[test]
+fn cga3d_versor_motor() {
+    for point_pair in cga3d_point_pairs() {
+        check_normalize!(point_pair.exp(), cga3d::Versor, cga3d::Versor, 16, 16);
+    }
+    let motor: cga3d::Motor = cga3d_screw().into();
+    check_normalize!(motor, cga3d::Motor, cga3d::Versor, 12, 16);
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use common::{assert_close, cga3d_point_pairs, cga3d_screw, ppga3d_lines, sta_bivectors, vga3d_bivectors};
+use geometric_algebra::{cga3d, epga1d, ppga1d, ppga3d, Exp, GeometricProduct, Sqrt};
+
+/// Checks that the square root squares back to the rotor
+macro_rules! check_sqrt {
+    ($rotor:expr, $m:literal) => {{
+        let rotor = $rotor;
+        let root = rotor.sqrt();
+        assert_close::<$m>(root.geometric_product(root).into(), rotor.into());
+    }};
+}
+
+#[test]
+fn epga1d_complex_number() {
+    for (z, root) in [
+        ([-3.0, 4.0], [1.0, 2.0]),
+        ([3.0, -4.0], [2.0, -1.0]),
+        ([0.0, 2.0], [1.0, 1.0]),
+        ([4.0, 0.0], [2.0, 0.0]),
+        ([0.6, 0.8], [0.894_427_2, 0.447_213_6]),
+    ] {
+        assert_close::<2>(epga1d::ComplexNumber::from(z).sqrt().into(), root);
+        check_sqrt!(epga1d::ComplexNumber::from(z), 2);
+    }
+}
+
+#[test]
+fn ppga1d_dual_number() {
+    assert_close::<2>(ppga1d::DualNumber::new(4.0, 4.0).sqrt().into(), [2.0, 1.0]);
+}
+
+#[test]
+fn ppga3d_rotor_translator_motor() {
+    check_sqrt!(ppga3d::Rotor::new(0.6, 0.0, 0.8, 0.0), 4);
+    check_sqrt!(ppga3d::Rotor::new(-0.6, 0.48, 0.0, -0.64), 4);
+    let translator = ppga3d::Translator::new(1.0, 0.5, -1.0, 2.0);
+    assert_close::<4>(translator.sqrt().into(), [1.0, 0.25, -0.5, 1.0]);
+    check_sqrt!(translator, 4);
+    for line in ppga3d_lines() {
+        check_sqrt!(line.exp(), 8);
+    }
+}
+
+#[test]
+fn sta_spinor() {
+    for bivector in sta_bivectors() {
+        check_sqrt!(bivector.exp(), 8);
+    }
+}
+
+#[test]
+fn vga3d_rotor() {
+    for bivector in vga3d_bivectors() {
+        check_sqrt!(bivector.exp(), 4);
+    }
+}
+
+#[test]
+fn cga3d_versor_motor() {
+    for point_pair in cga3d_point_pairs() {
+        check_sqrt!(point_pair.exp(), 16);
+    }
+    let versor = cga3d_screw();
+    let motor: cga3d::Motor = versor.into();
+    let root = motor.sqrt();
+    assert_close::<16>(root.geometric_product(root).into(), versor.into());
+}